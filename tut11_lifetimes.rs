@@ -95,6 +95,8 @@ fn main() {
         }
         &s[..]
     }
+    // the `tokenizer` module in tut3_ownerships_and_slicing.rs goes further (nth/last word,
+    // word and sentence iterators); every slice it returns is tied to the input's lifetime
     
     //--------------lifetime annotation in methods--------------
     impl<'a> ImportantExcerpt<'a> { // it's ok due to first elision rule
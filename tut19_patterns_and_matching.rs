@@ -134,6 +134,7 @@ fn main() {
             println!("Change the color to red {r}, green {g}, and blue {b}",)
         }
     }
+    // the `message_bus` module in tut5_enum_match_and_if_let.rs applies these variants to a state
     // Destructuring Structs and Tuples
     let ((feet, inches), Point { x, y }) = ((3, 10), Point { x: 3, y: -10 });
 
@@ -1,36 +1,1591 @@
-use rand::Rng;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::cmp::Ordering;
-use std::io;
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::process;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
 use colored::*;
 
 fn main() {
-    println!("Guess the number!");
+    let config = match GameConfig::from_args(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("{}", e.red());
+            process::exit(2);
+        }
+    };
 
-    let secret_number = rand::thread_rng().gen_range(1..=100);
+    let store = ScoreStore::new(match &config.scores_file {
+        Some(path) => PathBuf::from(path),
+        None => ScoreStore::default_path(),
+    });
+    let stdin = io::stdin();
+    let stdout = io::stdout();
 
-    loop {
-        println!("Please input your guess.");
+    let result = match &config.mode {
+        Mode::Play => play_and_record(&config, &store, stdin.lock(), stdout.lock()),
+        Mode::Scores => store.print_leaderboard(&mut stdout.lock()),
+        Mode::Reverse => play_reverse(&config, stdin.lock(), stdout.lock()).map(|_| ()),
+        Mode::Benchmark { games } => benchmark(&config, *games, &mut stdout.lock()),
+        Mode::Serve { addr } => TcpListener::bind(addr).and_then(|listener| {
+            println!("Serving guessing game rooms on {}", listener.local_addr()?);
+            serve(listener, config.clone())
+        }),
+        Mode::Connect { addr } => connect(&config, addr, stdin.lock()),
+    };
+    if let Err(e) = result {
+        eprintln!("{}", format!("I/O error: {e}").red());
+        process::exit(1);
+    }
+}
+
+fn play_and_record<R: BufRead, W: Write>(
+    config: &GameConfig,
+    store: &ScoreStore,
+    input: R,
+    output: W,
+) -> io::Result<()> {
+    let mut source: Box<dyn SecretSource> = match config.seed {
+        Some(seed) => Box::new(RandomSecret(StdRng::seed_from_u64(seed))),
+        None => Box::new(RandomSecret(rand::thread_rng())),
+    };
+    let started = Instant::now();
+    let outcome = play(config, source.as_mut(), input, output)?;
 
-        let mut guess = String::new();
+    let (won, attempts) = match outcome {
+        Outcome::Won { attempts } => (true, attempts),
+        Outcome::Lost { attempts, .. } => (false, attempts),
+        Outcome::Abandoned { .. } => return Ok(()), // unfinished games are not scored
+    };
+    let score = Score {
+        player: config.player.clone(),
+        difficulty: config.difficulty,
+        won,
+        attempts,
+        duration: started.elapsed(),
+    };
+    if let Err(e) = store.record(&score) {
+        eprintln!("{}", format!("could not save score: {e}").red());
+    }
+    Ok(())
+}
+
+//--------------game loop--------------
+// the loop only talks to `input`/`output`, so it can be driven headlessly
+// with a scripted transcript, e.g.
+//     let mut out = Vec::new();
+//     play(&config, &mut FixedSecret(42), "50\n42\n".as_bytes(), &mut out)?;
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Outcome {
+    Won { attempts: u32 },
+    Lost { attempts: u32, secret: u32 },
+    Abandoned { attempts: u32 }, // input ended before the game did
+}
+
+fn play<R: BufRead, W: Write>(
+    config: &GameConfig,
+    source: &mut dyn SecretSource,
+    mut input: R,
+    mut output: W,
+) -> io::Result<Outcome> {
+    writeln!(output, "Guess the number!")?;
+    writeln!(
+        output,
+        "Difficulty: {:?} ({}..={}, {})",
+        config.difficulty,
+        config.low,
+        config.high,
+        match config.max_attempts {
+            Some(max) => format!("{max} attempts"),
+            None => String::from("unlimited attempts"),
+        }
+    )?;
 
-        io::stdin()
-            .read_line(&mut guess)
-            .expect("Failed to read line");
+    writeln!(output, "Commands: quit, hint, history.")?;
 
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => continue,
+    let secret_number = source.secret(config.low, config.high);
+    let mut attempts = 0;
+    let mut hints = HintEngine::new(config.low, config.high);
+    let mut last_hint: Option<Hint> = None;
+    let mut history: Vec<u32> = Vec::new();
+
+    loop {
+        writeln!(output, "Please input your guess.")?;
+
+        let mut line = String::new();
+        let read = input.read_line(&mut line)?;
+
+        let guess = match parse_input(read, &line, config, &history) {
+            Ok(Input::Guess(num)) => num,
+            Ok(Input::Quit) => {
+                writeln!(output, "Bye! The secret number was {secret_number}.")?;
+                return Ok(Outcome::Abandoned { attempts });
+            }
+            Ok(Input::Hint) => {
+                if !config.hints {
+                    writeln!(output, "{}", "Hints are turned off for this game.".yellow())?;
+                    continue;
+                }
+                let (low, high) = hints.interval();
+                writeln!(output, "The number is between {low} and {high}.")?;
+                if let Some(hint) = last_hint {
+                    hint.write_to(&mut output)?;
+                }
+                continue;
+            }
+            Ok(Input::History) => {
+                if history.is_empty() {
+                    writeln!(output, "No guesses yet.")?;
+                }
+                for (i, guess) in history.iter().enumerate() {
+                    let result = if *guess < secret_number { "too small" } else { "too big" };
+                    writeln!(output, "{:>3}. {guess} ({result})", i + 1)?;
+                }
+                continue;
+            }
+            Err(InputError::Eof) => {
+                writeln!(output)?;
+                writeln!(output, "Goodbye!")?;
+                return Ok(Outcome::Abandoned { attempts });
+            }
+            Err(e) => {
+                writeln!(output, "{}", e.to_string().yellow())?;
+                continue;
+            }
         };
 
-        println!("You guessed: {guess}");
+        writeln!(output, "You guessed: {guess}")?;
+        attempts += 1;
+        history.push(guess);
+        let hint = hints.hint(guess, secret_number);
+        last_hint = Some(hint);
 
         match guess.cmp(&secret_number) {
-            Ordering::Less => println!("{}","Too small!".red()),
-            Ordering::Greater => println!("{}","Too big!".red()),
+            Ordering::Less => writeln!(output, "{}", "Too small!".red())?,
+            Ordering::Greater => writeln!(output, "{}", "Too big!".red())?,
             Ordering::Equal => {
-                println!("{}","You win!".green());
+                writeln!(output, "{}", "You win!".green())?;
+                return Ok(Outcome::Won { attempts });
+            }
+        }
+        if config.hints {
+            hint.write_to(&mut output)?;
+        }
+
+        if config.max_attempts.is_some_and(|max| attempts >= max) {
+            writeln!(output, "{}", "You lose!".red())?;
+            writeln!(output, "The secret number was {secret_number}.")?;
+            return Ok(Outcome::Lost { attempts, secret: secret_number });
+        }
+    }
+}
+
+//--------------input validation--------------
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Input {
+    Guess(u32),
+    Quit,
+    Hint,
+    History,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum InputError {
+    NotANumber(String),
+    OutOfRange { value: String, low: u32, high: u32 },
+    Duplicate(u32),
+    Eof, // Ctrl-D, or the end of a scripted transcript
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InputError::NotANumber(text) => {
+                write!(f, "'{text}' is not a number. Type a number, or quit, hint or history.")
+            }
+            InputError::OutOfRange { value, low, high } => {
+                write!(f, "{value} is out of range: the number is between {low} and {high}.")
+            }
+            InputError::Duplicate(value) => write!(f, "You already guessed {value}."),
+            InputError::Eof => write!(f, "no more input"),
+        }
+    }
+}
+
+impl Error for InputError {}
+
+// `read` is what read_line returned: 0 bytes means the input is closed
+fn parse_input(read: usize, line: &str, config: &GameConfig, history: &[u32]) -> Result<Input, InputError> {
+    if read == 0 {
+        return Err(InputError::Eof);
+    }
+    let text = line.trim();
+    match text.to_lowercase().as_str() {
+        "quit" | "exit" | "q" => return Ok(Input::Quit),
+        "hint" => return Ok(Input::Hint),
+        "history" => return Ok(Input::History),
+        _ => (),
+    }
+    // anything that looks like an integer but doesn't fit the range (negative,
+    // or too big even for u32) is reported as out of range, not as garbage
+    let digits = text.strip_prefix('-').unwrap_or(text);
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return Err(InputError::NotANumber(text.to_string()));
+    }
+    let value = match text.parse::<u32>() {
+        Ok(value) if (config.low..=config.high).contains(&value) => value,
+        _ => {
+            return Err(InputError::OutOfRange {
+                value: text.to_string(),
+                low: config.low,
+                high: config.high,
+            })
+        }
+    };
+    let guess = value;
+    if history.contains(&guess) {
+        return Err(InputError::Duplicate(guess));
+    }
+    Ok(Input::Guess(guess))
+}
+
+//--------------hints--------------
+// proximity is measured relative to the size of the whole range, so "hot"
+// means the same thing on easy and on hard
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Proximity {
+    Burning, // within 2% of the range
+    Hot,     // within 10%
+    Warm,    // within 25%
+    Cold,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Trend {
+    Closer,
+    Farther,
+    Same,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Hint {
+    distance: u32,
+    span: u32,
+    proximity: Proximity,
+    trend: Option<Trend>, // None for the first guess
+    wasted: Option<(u32, u32)>, // the interval the guess should have been in
+}
+
+impl Hint {
+    // 0.0 for the farthest possible guess, 1.0 for a perfect one
+    fn closeness(&self) -> f64 {
+        1.0 - self.distance as f64 / self.span as f64
+    }
+
+    // blue when cold fading through yellow to red when burning
+    fn color(&self) -> (u8, u8, u8) {
+        let t = self.closeness().clamp(0.0, 1.0);
+        if t < 0.5 {
+            let t = t * 2.0;
+            ((255.0 * t) as u8, (255.0 * t) as u8, (255.0 * (1.0 - t)) as u8)
+        } else {
+            let t = (t - 0.5) * 2.0;
+            (255, (255.0 * (1.0 - t)) as u8, 0)
+        }
+    }
+
+    fn write_to<W: Write>(&self, output: &mut W) -> io::Result<()> {
+        let mut text = String::from(match self.proximity {
+            Proximity::Burning => "Burning!",
+            Proximity::Hot => "Hot!",
+            Proximity::Warm => "Warm.",
+            Proximity::Cold => "Cold.",
+        });
+        match self.trend {
+            Some(Trend::Closer) => text.push_str(" Getting closer."),
+            Some(Trend::Farther) => text.push_str(" Getting farther."),
+            Some(Trend::Same) => text.push_str(" Same distance as last time."),
+            None => (),
+        }
+        let (r, g, b) = self.color();
+        writeln!(output, "{}", text.truecolor(r, g, b))?;
+        if let Some((low, high)) = self.wasted {
+            writeln!(
+                output,
+                "{}",
+                format!("Wasted guess: you already knew it was between {low} and {high}.").yellow()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+struct HintEngine {
+    low: u32, // the interval the secret can still be in
+    high: u32,
+    span: u32,
+    previous_distance: Option<u32>,
+}
+
+impl HintEngine {
+    fn new(low: u32, high: u32) -> HintEngine {
+        HintEngine {
+            low,
+            high,
+            span: (high - low).max(1),
+            previous_distance: None,
+        }
+    }
+
+    fn interval(&self) -> (u32, u32) {
+        (self.low, self.high)
+    }
+
+    fn hint(&mut self, guess: u32, secret: u32) -> Hint {
+        let distance = guess.abs_diff(secret);
+        let percent = distance as f64 * 100.0 / self.span as f64;
+        let proximity = if percent <= 2.0 || distance <= 1 {
+            Proximity::Burning
+        } else if percent <= 10.0 {
+            Proximity::Hot
+        } else if percent <= 25.0 {
+            Proximity::Warm
+        } else {
+            Proximity::Cold
+        };
+        let trend = self.previous_distance.map(|previous| match distance.cmp(&previous) {
+            Ordering::Less => Trend::Closer,
+            Ordering::Greater => Trend::Farther,
+            Ordering::Equal => Trend::Same,
+        });
+        let wasted = if guess < self.low || guess > self.high {
+            Some((self.low, self.high))
+        } else {
+            None
+        };
+
+        match guess.cmp(&secret) {
+            Ordering::Less => self.low = self.low.max(guess + 1),
+            Ordering::Greater => self.high = self.high.min(guess - 1),
+            Ordering::Equal => (),
+        }
+        self.previous_distance = Some(distance);
+
+        Hint { distance, span: self.span, proximity, trend, wasted }
+    }
+}
+
+//--------------reverse game--------------
+// the player thinks of a number and answers each guess with
+// higher/lower/correct; the program narrows the interval with binary search
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ReverseOutcome {
+    Found { attempts: u32, number: u32 },
+    Contradiction { attempts: u32 }, // the answers ruled out every number
+    Abandoned { attempts: u32 },
+}
+
+fn play_reverse<R: BufRead, W: Write>(
+    config: &GameConfig,
+    mut input: R,
+    mut output: W,
+) -> io::Result<ReverseOutcome> {
+    writeln!(
+        output,
+        "Think of a number between {} and {}, and I'll guess it!",
+        config.low, config.high
+    )?;
+    // None once the answers have ruled out every number
+    let mut interval = Some((config.low, config.high));
+    let mut attempts = 0;
+
+    loop {
+        let (low, high) = match interval {
+            Some(interval) => interval,
+            None => {
+                writeln!(
+                    output,
+                    "{}",
+                    "Your answers contradict each other: no number fits all of them.".red()
+                )?;
+                return Ok(ReverseOutcome::Contradiction { attempts });
+            }
+        };
+        let guess = low + (high - low) / 2;
+        attempts += 1;
+
+        let answer = loop {
+            writeln!(output, "Is it {guess}? (higher/lower/correct)")?;
+            let mut answer = String::new();
+            if input.read_line(&mut answer)? == 0 {
+                return Ok(ReverseOutcome::Abandoned { attempts });
+            }
+            // "higher" means the player's number is higher than the guess
+            match answer.trim().to_lowercase().as_str() {
+                "h" | "higher" => break Ordering::Greater,
+                "l" | "lower" => break Ordering::Less,
+                "c" | "correct" => break Ordering::Equal,
+                _ => writeln!(output, "Please answer higher, lower or correct.")?,
+            }
+        };
+
+        // higher than u32::MAX or lower than 0 is as contradictory as an empty interval
+        interval = match answer {
+            Ordering::Greater => guess.checked_add(1).map(|low| (low, high)),
+            Ordering::Less => guess.checked_sub(1).map(|high| (low, high)),
+            Ordering::Equal => {
+                writeln!(output, "{}", format!("Got it in {attempts} guesses!").green())?;
+                return Ok(ReverseOutcome::Found { attempts, number: guess });
+            }
+        }
+        .filter(|(low, high)| low <= high);
+    }
+}
+
+//--------------solver strategies--------------
+// a strategy picks the next guess from the interval the secret can still be
+// in; the previous guess and its hint are passed along for strategies that
+// want more than the Too small/Too big direction
+trait Strategy {
+    fn next_guess(&mut self, low: u32, high: u32, last: Option<(u32, Hint)>) -> u32;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum StrategyKind {
+    Binary,
+    Random,
+    Interpolation,
+}
+
+impl StrategyKind {
+    const ALL: [StrategyKind; 3] = [StrategyKind::Binary, StrategyKind::Random, StrategyKind::Interpolation];
+
+    fn parse(name: &str) -> Result<StrategyKind, String> {
+        match name.trim().to_lowercase().as_str() {
+            "binary" => Ok(StrategyKind::Binary),
+            "random" => Ok(StrategyKind::Random),
+            "interpolation" => Ok(StrategyKind::Interpolation),
+            other => Err(format!("unknown strategy '{other}' (expected binary, random, interpolation or all)")),
+        }
+    }
+
+    fn build(self, seed: u64) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Binary => Box::new(BinarySearch),
+            // offset so the guesses are not the same sequence as the secrets
+            StrategyKind::Random => Box::new(RandomGuess(StdRng::seed_from_u64(seed ^ 0x5eed))),
+            StrategyKind::Interpolation => Box::new(Interpolation),
+        }
+    }
+}
+
+struct BinarySearch;
+
+impl Strategy for BinarySearch {
+    fn next_guess(&mut self, low: u32, high: u32, _last: Option<(u32, Hint)>) -> u32 {
+        low + (high - low) / 2
+    }
+}
+
+// never wastes a guess, but otherwise ignores everything it has learnt
+struct RandomGuess(StdRng);
+
+impl Strategy for RandomGuess {
+    fn next_guess(&mut self, low: u32, high: u32, _last: Option<(u32, Hint)>) -> u32 {
+        self.0.gen_range(low..=high)
+    }
+}
+
+// uses the proximity bucket of the last hint to estimate how far away the
+// secret is, then bisects the part of the interval that matches that estimate
+struct Interpolation;
+
+impl Strategy for Interpolation {
+    fn next_guess(&mut self, low: u32, high: u32, last: Option<(u32, Hint)>) -> u32 {
+        let (guess, hint) = match last {
+            Some(last) => last,
+            None => return low + (high - low) / 2,
+        };
+        // the buckets are relative to the whole range, not what is left of it
+        let span = hint.span as f64;
+        let (near, far) = match hint.proximity {
+            Proximity::Burning => (0.0, 0.02),
+            Proximity::Hot => (0.02, 0.10),
+            Proximity::Warm => (0.10, 0.25),
+            Proximity::Cold => (0.25, 1.0),
+        };
+        let (near, far) = ((span * near) as u32, (span * far).ceil() as u32);
+        // the interval has already been narrowed, so it tells us which side we are on
+        let (from, to) = if guess < low {
+            (guess.saturating_add(near).max(low), guess.saturating_add(far).min(high))
+        } else {
+            (guess.saturating_sub(far).max(low), guess.saturating_sub(near).min(high))
+        };
+        if from > to {
+            return low + (high - low) / 2;
+        }
+        from + (to - from) / 2
+    }
+}
+
+// plays one game to the end and returns the number of guesses it took
+fn solve(strategy: &mut dyn Strategy, low: u32, high: u32, secret: u32) -> u32 {
+    let mut hints = HintEngine::new(low, high);
+    let (mut low, mut high) = (low, high);
+    let mut last = None;
+    let mut attempts = 0;
+    loop {
+        let guess = strategy.next_guess(low, high, last).clamp(low, high);
+        attempts += 1;
+        let hint = hints.hint(guess, secret);
+        // guess < secret <= u32::MAX and guess > secret >= 0, so neither step can overflow
+        match guess.cmp(&secret) {
+            Ordering::Less => low = guess + 1,
+            Ordering::Greater => high = guess - 1,
+            Ordering::Equal => return attempts,
+        }
+        last = Some((guess, hint));
+    }
+}
+
+fn benchmark<W: Write>(config: &GameConfig, games: u32, output: &mut W) -> io::Result<()> {
+    let seed = config.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    let kinds = match config.strategy {
+        Some(kind) => vec![kind],
+        None => StrategyKind::ALL.to_vec(),
+    };
+    writeln!(
+        output,
+        "Benchmarking {games} games on {}..={} (seed {seed})",
+        config.low, config.high
+    )?;
+
+    for kind in kinds {
+        // every strategy gets the same sequence of secrets
+        let mut secrets = RandomSecret(StdRng::seed_from_u64(seed));
+        let mut strategy = kind.build(seed);
+        let mut results: Vec<u32> = (0..games)
+            .map(|_| {
+                let secret = secrets.secret(config.low, config.high);
+                solve(strategy.as_mut(), config.low, config.high, secret)
+            })
+            .collect();
+        results.sort();
+
+        let mean = results.iter().map(|&n| n as f64).sum::<f64>() / results.len() as f64;
+        let median = results[results.len() / 2];
+        let wins = match config.max_attempts {
+            Some(max) => results.iter().filter(|&&n| n <= max).count(),
+            None => results.len(),
+        };
+        writeln!(output)?;
+        writeln!(output, "{}", format!("{kind:?}").bold())?;
+        writeln!(
+            output,
+            "min {}  max {}  mean {mean:.2}  median {median}  within attempt limit {:.1}%",
+            results[0],
+            results[results.len() - 1],
+            wins as f64 * 100.0 / results.len() as f64
+        )?;
+
+        let mut counts: Vec<(u32, usize)> = Vec::new();
+        for &n in &results {
+            match counts.last_mut() {
+                Some((attempts, count)) if *attempts == n => *count += 1,
+                _ => counts.push((n, 1)),
+            }
+        }
+        let largest = counts.iter().map(|&(_, count)| count).max().unwrap_or(1);
+        for (attempts, count) in counts {
+            let bar = "#".repeat((count * 40).div_ceil(largest));
+            writeln!(output, "{attempts:>4} | {bar} {count}")?;
+        }
+    }
+    Ok(())
+}
+
+//--------------multiplayer--------------
+// line-based protocol, one command per line
+//   client -> server:  JOIN <name> <room> | START | GUESS <n> | QUIT
+//   server -> client:  WELCOME <room> <players...> | JOINED <name> | LEFT <name>
+//                      START <low> <high> | TURN <name>
+//                      RESULT <name> <guess> SMALL|BIG
+//                      WINNER <name> <secret> <attempts> | ERROR <message>
+// players take turns in the order they joined; the first correct guess wins
+// and anyone in the room can START the next round. attempt limits do not
+// apply to multiplayer games.
+//
+// every connection gets a reader thread that forwards its lines over a
+// channel, and a single game thread owns all the rooms, so no locks are needed
+enum Event {
+    Connected { id: usize, stream: TcpStream },
+    Line { id: usize, line: String },
+    Disconnected { id: usize },
+}
+
+struct Player {
+    id: usize,
+    name: String,
+    stream: TcpStream,
+}
+
+#[derive(Default)]
+struct Room {
+    players: Vec<Player>, // in turn order
+    secret: Option<u32>,  // None between rounds
+    turn: usize,
+    attempts: u32,
+}
+
+impl Room {
+    fn broadcast(&mut self, message: &str) {
+        // a failed write means the reader thread will report the disconnect
+        for player in &mut self.players {
+            let _ = writeln!(player.stream, "{message}");
+        }
+    }
+
+    fn announce_turn(&mut self) {
+        if self.secret.is_some() && !self.players.is_empty() {
+            self.turn %= self.players.len();
+            let name = self.players[self.turn].name.clone();
+            self.broadcast(&format!("TURN {name}"));
+        }
+    }
+}
+
+// accepts clients forever; bind to 127.0.0.1:0 to get a free port for tests
+fn serve(listener: TcpListener, config: GameConfig) -> io::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || run_rooms(config, receiver));
+
+    for (id, stream) in listener.incoming().enumerate() {
+        // one bad connection (reset before accept, out of descriptors...)
+        // shouldn't take the whole server down with it
+        let (stream, reader) = match stream.and_then(|s| Ok((s.try_clone()?, s))) {
+            Ok((clone, stream)) => (stream, BufReader::new(clone)),
+            Err(e) => {
+                eprintln!("{}", format!("could not accept a connection: {e}").red());
+                continue;
+            }
+        };
+        let sender = sender.clone();
+        if sender.send(Event::Connected { id, stream }).is_err() {
+            break;
+        }
+        thread::spawn(move || {
+            for line in reader.lines() {
+                match line {
+                    Ok(line) => {
+                        if sender.send(Event::Line { id, line }).is_err() {
+                            return;
+                        }
+                    }
+                    Err(_) => break,
+                }
+            }
+            let _ = sender.send(Event::Disconnected { id });
+        });
+    }
+    Ok(())
+}
+
+fn run_rooms(config: GameConfig, events: mpsc::Receiver<Event>) {
+    let mut source = RandomSecret(match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    });
+    let mut pending: HashMap<usize, TcpStream> = HashMap::new(); // connected, not joined yet
+    let mut rooms: HashMap<String, Room> = HashMap::new();
+    let mut room_of: HashMap<usize, String> = HashMap::new();
+
+    for event in events {
+        match event {
+            Event::Connected { id, stream } => {
+                pending.insert(id, stream);
+            }
+            Event::Disconnected { id } => {
+                pending.remove(&id);
+                if let Some(name) = room_of.remove(&id) {
+                    leave(&mut rooms, &name, id);
+                }
+            }
+            Event::Line { id, line } => {
+                let mut words = line.split_whitespace();
+                let command = words.next().unwrap_or("").to_uppercase();
+                let room_name = match room_of.get(&id) {
+                    Some(name) => name.clone(),
+                    None => {
+                        let stream = match pending.get_mut(&id) {
+                            Some(stream) => stream,
+                            None => continue,
+                        };
+                        match (command.as_str(), words.next(), words.next()) {
+                            ("JOIN", Some(name), Some(room_name)) => {
+                                let room = rooms.entry(room_name.to_string()).or_default();
+                                if room.players.iter().any(|p| p.name == name) {
+                                    let _ = writeln!(stream, "ERROR name {name} is taken");
+                                    continue;
+                                }
+                                let mut stream = pending.remove(&id).unwrap();
+                                let names: Vec<&str> = room.players.iter().map(|p| p.name.as_str()).collect();
+                                let _ = writeln!(stream, "{}", format!("WELCOME {room_name} {}", names.join(" ")).trim_end());
+                                room.broadcast(&format!("JOINED {name}"));
+                                room.players.push(Player { id, name: name.to_string(), stream });
+                                room_of.insert(id, room_name.to_string());
+                            }
+                            _ => {
+                                let _ = writeln!(stream, "ERROR expected JOIN <name> <room>");
+                            }
+                        }
+                        continue;
+                    }
+                };
+
+                let room = rooms.get_mut(&room_name).unwrap();
+                let index = room.players.iter().position(|p| p.id == id).unwrap();
+                match command.as_str() {
+                    "START" => {
+                        if room.secret.is_some() {
+                            let _ = writeln!(room.players[index].stream, "ERROR a round is already running");
+                            continue;
+                        }
+                        room.secret = Some(source.secret(config.low, config.high));
+                        room.attempts = 0;
+                        room.broadcast(&format!("START {} {}", config.low, config.high));
+                        room.announce_turn();
+                    }
+                    "GUESS" => {
+                        let secret = match room.secret {
+                            Some(secret) => secret,
+                            None => {
+                                let _ = writeln!(room.players[index].stream, "ERROR no round is running, send START");
+                                continue;
+                            }
+                        };
+                        if index != room.turn {
+                            let _ = writeln!(room.players[index].stream, "ERROR it is not your turn");
+                            continue;
+                        }
+                        let guess: u32 = match words.next().map(str::parse) {
+                            Some(Ok(guess)) => guess,
+                            _ => {
+                                let _ = writeln!(room.players[index].stream, "ERROR expected GUESS <number>");
+                                continue;
+                            }
+                        };
+                        // like a typo in single player, this doesn't use up the turn
+                        if !(config.low..=config.high).contains(&guess) {
+                            let _ = writeln!(
+                                room.players[index].stream,
+                                "ERROR guess must be between {} and {}",
+                                config.low, config.high
+                            );
+                            continue;
+                        }
+                        room.attempts += 1;
+                        let name = room.players[index].name.clone();
+                        match guess.cmp(&secret) {
+                            Ordering::Less => room.broadcast(&format!("RESULT {name} {guess} SMALL")),
+                            Ordering::Greater => room.broadcast(&format!("RESULT {name} {guess} BIG")),
+                            Ordering::Equal => {
+                                let attempts = room.attempts;
+                                room.broadcast(&format!("WINNER {name} {secret} {attempts}"));
+                                room.secret = None;
+                                continue;
+                            }
+                        }
+                        room.turn += 1;
+                        room.announce_turn();
+                    }
+                    "QUIT" => {
+                        room_of.remove(&id);
+                        leave(&mut rooms, &room_name, id);
+                    }
+                    _ => {
+                        let _ = writeln!(room.players[index].stream, "ERROR unknown command {command}");
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn leave(rooms: &mut HashMap<String, Room>, room_name: &str, id: usize) {
+    let room = match rooms.get_mut(room_name) {
+        Some(room) => room,
+        None => return,
+    };
+    let index = match room.players.iter().position(|p| p.id == id) {
+        Some(index) => index,
+        None => return,
+    };
+    let player = room.players.remove(index);
+    let _ = player.stream.shutdown(std::net::Shutdown::Both);
+    if room.players.is_empty() {
+        rooms.remove(room_name);
+        return;
+    }
+    room.broadcast(&format!("LEFT {}", player.name));
+    // keep the turn pointing at whoever was next
+    let was_their_turn = index == room.turn;
+    if index < room.turn {
+        room.turn -= 1;
+    }
+    if was_their_turn {
+        room.announce_turn();
+    }
+}
+
+// a small terminal client: bare numbers are sent as guesses, and the
+// server's messages are printed as they arrive
+fn connect<R: BufRead>(config: &GameConfig, addr: &str, input: R) -> io::Result<()> {
+    let mut stream = TcpStream::connect(addr)?;
+    let reader = BufReader::new(stream.try_clone()?);
+    let name = config.player.split_whitespace().collect::<Vec<_>>().join("_");
+    writeln!(stream, "JOIN {name} {}", config.room)?;
+    println!("Type `start` to begin a round, a number to guess, or `quit`.");
+
+    let printer = thread::spawn(move || {
+        for line in reader.lines().map_while(Result::ok) {
+            println!("{}", describe(&line));
+        }
+    });
+
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+        let message = if line.parse::<u32>().is_ok() {
+            format!("GUESS {line}")
+        } else {
+            line.to_uppercase()
+        };
+        writeln!(stream, "{message}")?;
+        if message == "QUIT" {
+            break;
+        }
+    }
+    let _ = stream.shutdown(std::net::Shutdown::Write);
+    let _ = printer.join();
+    Ok(())
+}
+
+// what the terminal client prints for one line from the server
+fn describe(line: &str) -> String {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    match words.as_slice() {
+        ["RESULT", who, guess, "SMALL"] => format!("{who} guessed {guess}: {}", "Too small!".red()),
+        ["RESULT", who, guess, "BIG"] => format!("{who} guessed {guess}: {}", "Too big!".red()),
+        ["WINNER", who, secret, attempts] => {
+            format!("{who} wins! The number was {secret} ({attempts} guesses in total).").green().to_string()
+        }
+        ["TURN", who] => format!("It's {who}'s turn."),
+        ["START", low, high] => format!("New round: guess a number between {low} and {high}."),
+        ["ERROR", ..] => {
+            let message = line.trim_start().strip_prefix("ERROR").map(str::trim_start).unwrap_or(line);
+            message.red().to_string()
+        }
+        _ => line.to_string(),
+    }
+}
+
+//--------------secret number source--------------
+trait SecretSource {
+    fn secret(&mut self, low: u32, high: u32) -> u32;
+}
+
+// any rng works: thread_rng() for normal play, StdRng::seed_from_u64 for --seed
+struct RandomSecret<R: Rng>(R);
+
+impl<R: Rng> SecretSource for RandomSecret<R> {
+    fn secret(&mut self, low: u32, high: u32) -> u32 {
+        self.0.gen_range(low..=high)
+    }
+}
+
+// always hands out the same number, handy for scripted sessions
+#[cfg(test)]
+struct FixedSecret(u32);
+
+#[cfg(test)]
+impl SecretSource for FixedSecret {
+    fn secret(&mut self, low: u32, high: u32) -> u32 {
+        self.0.clamp(low, high)
+    }
+}
+
+//--------------difficulty profiles--------------
+// easy: 1..=10 in 5 attempts, normal: 1..=100 in 10, hard: 1..=1000 in 10
+// custom: starts from normal and is overridden by --min/--max/--attempts
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Custom];
+
+    fn parse(name: &str) -> Result<Difficulty, String> {
+        match name.trim().to_lowercase().as_str() {
+            "easy" => Ok(Difficulty::Easy),
+            "normal" => Ok(Difficulty::Normal),
+            "hard" => Ok(Difficulty::Hard),
+            "custom" => Ok(Difficulty::Custom),
+            other => Err(format!("unknown difficulty '{other}' (expected easy, normal, hard or custom)")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Mode {
+    Play,
+    Scores,
+    Reverse,                   // the program guesses the player's number
+    Benchmark { games: u32 }, // the program plays against itself
+    Serve { addr: String },    // host multiplayer rooms over TCP
+    Connect { addr: String },  // join a multiplayer room
+}
+
+#[derive(Debug, Clone)]
+struct GameConfig {
+    difficulty: Difficulty,
+    low: u32,
+    high: u32,
+    max_attempts: Option<u32>, // None means the game only ends on a win
+    seed: Option<u64>,         // Some makes the secret number reproducible
+    player: String,
+    scores_file: Option<String>, // None means the user's data dir
+    mode: Mode,
+    strategy: Option<StrategyKind>, // None benchmarks every strategy
+    hints: bool, // warmer/colder feedback after each wrong guess
+    room: String, // multiplayer room to join
+}
+
+impl GameConfig {
+    fn for_difficulty(difficulty: Difficulty) -> GameConfig {
+        let (low, high, max_attempts) = match difficulty {
+            Difficulty::Easy => (1, 10, Some(5)),
+            Difficulty::Normal | Difficulty::Custom => (1, 100, Some(10)),
+            Difficulty::Hard => (1, 1000, Some(10)),
+        };
+        GameConfig {
+            difficulty,
+            low,
+            high,
+            max_attempts,
+            seed: None,
+            player: env::var("USER")
+                .or_else(|_| env::var("USERNAME"))
+                .unwrap_or_else(|_| String::from("anonymous")),
+            scores_file: None,
+            mode: Mode::Play,
+            strategy: None,
+            room: String::from("lobby"),
+            hints: true,
+        }
+    }
+
+    // defaults < config file < command-line flags
+    // usage: [--config <file>] [--difficulty <name> | --easy | --normal | --hard]
+    //        [--min <n>] [--max <n>] [--attempts <n|unlimited>] [--seed <n>]
+    //        [--player <name>] [--scores-file <file>] [--scores] [--no-hints]
+    //        [--reverse] [--benchmark <games>] [--strategy <name>]
+    //        [--serve <addr>] [--connect <addr>] [--room <name>]
+    fn from_args<I: Iterator<Item = String>>(args: I) -> Result<GameConfig, String> {
+        let args: Vec<String> = args.collect();
+        let mut config = GameConfig::for_difficulty(Difficulty::Normal);
+
+        // the config file is applied first so the flags always win
+        if let Some(i) = args.iter().position(|a| a == "--config") {
+            let path = args.get(i + 1).ok_or("--config needs a file path")?;
+            let contents = fs::read_to_string(path)
+                .map_err(|e| format!("could not read config file {path}: {e}"))?;
+            config.apply_file(&contents)?;
+        }
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--config" => {
+                    args.next();
+                }
+                "--easy" => config.set_difficulty(Difficulty::Easy),
+                "--normal" => config.set_difficulty(Difficulty::Normal),
+                "--hard" => config.set_difficulty(Difficulty::Hard),
+                "--scores" => config.mode = Mode::Scores,
+                "--reverse" => config.mode = Mode::Reverse,
+                "--no-hints" => config.hints = false,
+                "--difficulty" | "--min" | "--max" | "--attempts" | "--seed" | "--player"
+                | "--scores-file" | "--benchmark" | "--strategy" | "--serve" | "--connect"
+                | "--room" => {
+                    let value = args.next().ok_or(format!("{arg} needs a value"))?;
+                    config.set(&arg[2..].replace('-', "_"), &value)?;
+                }
+                other => return Err(format!("unknown argument '{other}'")),
+            }
+        }
+
+        config.validate()?;
+        Ok(config)
+    }
+
+    // the file uses simple `key = value` lines, e.g.
+    //     # my session
+    //     difficulty = "hard"
+    //     attempts = 12
+    fn apply_file(&mut self, contents: &str) -> Result<(), String> {
+        for (number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(format!("config line {}: expected `key = value`", number + 1))?;
+            let value = value.trim().trim_matches('"');
+            self.set(key.trim(), value)
+                .map_err(|e| format!("config line {}: {e}", number + 1))?;
+        }
+        Ok(())
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        let number = |value: &str| {
+            value
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("{key} must be a positive number, got '{value}'"))
+        };
+        match key {
+            "difficulty" => self.set_difficulty(Difficulty::parse(value)?),
+            "min" => {
+                self.low = number(value)?;
+                self.difficulty = Difficulty::Custom;
+            }
+            "max" => {
+                self.high = number(value)?;
+                self.difficulty = Difficulty::Custom;
+            }
+            "attempts" => {
+                self.max_attempts = match value {
+                    "unlimited" | "0" => None,
+                    _ => Some(number(value)?),
+                };
+                self.difficulty = Difficulty::Custom;
+            }
+            "seed" => {
+                let seed = value
+                    .trim()
+                    .parse::<u64>()
+                    .map_err(|_| format!("seed must be a positive number, got '{value}'"))?;
+                self.seed = Some(seed);
+            }
+            "player" => {
+                if value.trim().is_empty() {
+                    return Err(String::from("player name must not be empty"));
+                }
+                self.player = value.trim().to_string();
+            }
+            "scores_file" => self.scores_file = Some(value.to_string()),
+            "benchmark" => {
+                let games = number(value)?;
+                if games == 0 {
+                    return Err(String::from("benchmark needs at least one game"));
+                }
+                self.mode = Mode::Benchmark { games };
+            }
+            "strategy" => {
+                self.strategy = match value.trim() {
+                    "all" => None,
+                    name => Some(StrategyKind::parse(name)?),
+                };
+            }
+            "serve" => self.mode = Mode::Serve { addr: value.trim().to_string() },
+            "connect" => self.mode = Mode::Connect { addr: value.trim().to_string() },
+            "room" => {
+                if value.trim().is_empty() || value.contains(char::is_whitespace) {
+                    return Err(format!("room name must be a single word, got '{value}'"));
+                }
+                self.room = value.trim().to_string();
+            }
+            "hints" => {
+                self.hints = value
+                    .trim()
+                    .parse()
+                    .map_err(|_| format!("hints must be true or false, got '{value}'"))?;
+            }
+            other => return Err(format!("unknown setting '{other}'")),
+        }
+        Ok(())
+    }
+
+    fn set_difficulty(&mut self, difficulty: Difficulty) {
+        // custom keeps whatever range was already configured
+        if difficulty != Difficulty::Custom {
+            let profile = GameConfig::for_difficulty(difficulty);
+            self.low = profile.low;
+            self.high = profile.high;
+            self.max_attempts = profile.max_attempts;
+        }
+        self.difficulty = difficulty;
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.low > self.high {
+            return Err(format!("min ({}) must not be greater than max ({})", self.low, self.high));
+        }
+        Ok(())
+    }
+}
+
+//--------------high scores--------------
+// every finished game is appended to a small TOML file:
+//     [[score]]
+//     player = "ferris"
+//     difficulty = "Normal"
+//     won = true
+//     attempts = 6
+//     duration_ms = 23140
+#[derive(Debug, Clone)]
+struct Score {
+    player: String,
+    difficulty: Difficulty,
+    won: bool,
+    attempts: u32,
+    duration: Duration,
+}
+
+#[derive(Debug, Default)]
+struct PlayerStats {
+    games: u32,
+    wins: u32,
+    total_win_attempts: u32,
+    best_streak: u32,
+}
+
+impl PlayerStats {
+    fn average_attempts(&self) -> Option<f64> {
+        if self.wins == 0 {
+            None
+        } else {
+            Some(self.total_win_attempts as f64 / self.wins as f64)
+        }
+    }
+}
+
+struct ScoreStore {
+    path: PathBuf,
+}
+
+impl ScoreStore {
+    fn new(path: PathBuf) -> ScoreStore {
+        ScoreStore { path }
+    }
+
+    // $XDG_DATA_HOME, then ~/.local/share, then %APPDATA%, then the current dir
+    fn default_path() -> PathBuf {
+        let base = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+            .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+            .unwrap_or_else(|| PathBuf::from("."));
+        base.join("guessing_game").join("scores.toml")
+    }
+
+    fn load(&self) -> io::Result<Vec<Score>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+        parse_scores(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    fn record(&self, score: &Score) -> io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "[[score]]")?;
+        writeln!(file, "player = \"{}\"", escape(&score.player))?;
+        writeln!(file, "difficulty = \"{:?}\"", score.difficulty)?;
+        writeln!(file, "won = {}", score.won)?;
+        writeln!(file, "attempts = {}", score.attempts)?;
+        writeln!(file, "duration_ms = {}", score.duration.as_millis())?;
+        writeln!(file)
+    }
+
+    // wins at one difficulty sorted by fewest attempts, then fastest time;
+    // six attempts on hard and six on easy aren't the same achievement
+    fn leaderboard(&self, difficulty: Difficulty, limit: usize) -> io::Result<Vec<Score>> {
+        let mut wins: Vec<Score> = self
+            .load()?
+            .into_iter()
+            .filter(|s| s.won && s.difficulty == difficulty)
+            .collect();
+        wins.sort_by(|a, b| a.attempts.cmp(&b.attempts).then(a.duration.cmp(&b.duration)));
+        wins.truncate(limit);
+        Ok(wins)
+    }
+
+    // games are stored in the order they were played, so a streak is simply
+    // the longest run of consecutive wins for that player
+    fn stats(&self) -> io::Result<Vec<(String, PlayerStats)>> {
+        let mut stats: Vec<(String, PlayerStats)> = Vec::new();
+        let mut streaks: Vec<u32> = Vec::new();
+        for score in self.load()? {
+            let i = match stats.iter().position(|(name, _)| *name == score.player) {
+                Some(i) => i,
+                None => {
+                    stats.push((score.player.clone(), PlayerStats::default()));
+                    streaks.push(0);
+                    stats.len() - 1
+                }
+            };
+            let player = &mut stats[i].1;
+            player.games += 1;
+            if score.won {
+                player.wins += 1;
+                player.total_win_attempts += score.attempts;
+                streaks[i] += 1;
+                player.best_streak = player.best_streak.max(streaks[i]);
+            } else {
+                streaks[i] = 0;
+            }
+        }
+        stats.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(stats)
+    }
+
+    fn print_leaderboard<W: Write>(&self, output: &mut W) -> io::Result<()> {
+        writeln!(output, "{}", "High scores".bold())?;
+        let mut any = false;
+        for difficulty in Difficulty::ALL {
+            let leaderboard = self.leaderboard(difficulty, 10)?;
+            if leaderboard.is_empty() {
+                continue;
+            }
+            any = true;
+            writeln!(output, "{difficulty:?}")?;
+            for (rank, score) in leaderboard.iter().enumerate() {
+                writeln!(
+                    output,
+                    "{:>2}. {:<16} {:>3} attempts {:>8.1}s",
+                    rank + 1,
+                    score.player,
+                    score.attempts,
+                    score.duration.as_secs_f64()
+                )?;
+            }
+        }
+        if !any {
+            writeln!(output, "No games won yet.")?;
+        }
+
+        writeln!(output)?;
+        writeln!(output, "{}", "Player stats".bold())?;
+        for (name, stats) in self.stats()? {
+            let average = match stats.average_attempts() {
+                Some(average) => format!("{average:.1}"),
+                None => String::from("-"),
+            };
+            writeln!(
+                output,
+                "{:<16} games {:>3}  wins {:>3}  avg attempts {:>5}  best streak {:>3}",
+                name, stats.games, stats.wins, average, stats.best_streak
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+// strips exactly one pair of surrounding quotes and undoes `escape`
+fn unescape(value: &str) -> String {
+    let value = value.strip_prefix('"').unwrap_or(value);
+    let value = value.strip_suffix('"').unwrap_or(value);
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+fn parse_scores(contents: &str) -> Result<Vec<Score>, String> {
+    let mut scores = Vec::new();
+    let mut current: Option<Score> = None;
+    for (number, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line == "[[score]]" {
+            scores.extend(current.take());
+            current = Some(Score {
+                player: String::new(),
+                difficulty: Difficulty::Normal,
+                won: false,
+                attempts: 0,
+                duration: Duration::ZERO,
+            });
+            continue;
+        }
+        let error = || format!("scores line {}: could not parse `{line}`", number + 1);
+        let score = current.as_mut().ok_or_else(error)?;
+        let (key, value) = line.split_once('=').ok_or_else(error)?;
+        let value = value.trim();
+        match key.trim() {
+            "player" => score.player = unescape(value),
+            "difficulty" => score.difficulty = Difficulty::parse(value.trim_matches('"'))?,
+            "won" => score.won = value.parse().map_err(|_| error())?,
+            "attempts" => score.attempts = value.parse().map_err(|_| error())?,
+            "duration_ms" => {
+                score.duration = Duration::from_millis(value.parse().map_err(|_| error())?)
+            }
+            _ => {} // unknown keys are ignored so newer files still load
+        }
+    }
+    scores.extend(current);
+    Ok(scores)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+
+    // plays a scripted game against a known secret and returns the outcome
+    // and the transcript, without colours
+    fn play_script(config: &GameConfig, secret: u32, script: &str) -> (Outcome, Vec<String>) {
+        colored::control::set_override(false);
+        let mut output: Vec<u8> = Vec::new();
+        let outcome = play(config, &mut FixedSecret(secret), script.as_bytes(), &mut output).unwrap();
+        let transcript = String::from_utf8(output).unwrap().lines().map(String::from).collect();
+        (outcome, transcript)
+    }
+
+    fn quiet_config() -> GameConfig {
+        let mut config = GameConfig::for_difficulty(Difficulty::Normal);
+        config.hints = false;
+        config
+    }
+
+    #[test]
+    fn scripted_win() {
+        let (outcome, transcript) = play_script(&quiet_config(), 42, "50\n10\n42\n");
+        assert_eq!(outcome, Outcome::Won { attempts: 3 });
+        assert_eq!(
+            transcript,
+            [
+                "Guess the number!",
+                "Difficulty: Normal (1..=100, 10 attempts)",
+                "Commands: quit, hint, history.",
+                "Please input your guess.",
+                "You guessed: 50",
+                "Too big!",
+                "Please input your guess.",
+                "You guessed: 10",
+                "Too small!",
+                "Please input your guess.",
+                "You guessed: 42",
+                "You win!",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_input_does_not_cost_an_attempt() {
+        let (outcome, transcript) = play_script(&quiet_config(), 7, "abc\n500\n3\n3\n7\n");
+        assert_eq!(outcome, Outcome::Won { attempts: 2 });
+        assert!(transcript.contains(&String::from("'abc' is not a number. Type a number, or quit, hint or history.")));
+        assert!(transcript.contains(&String::from("500 is out of range: the number is between 1 and 100.")));
+        assert!(transcript.contains(&String::from("You already guessed 3.")));
+    }
+
+    #[test]
+    fn running_out_of_attempts_loses() {
+        let mut config = quiet_config();
+        config.max_attempts = Some(2);
+        let (outcome, transcript) = play_script(&config, 42, "1\n99\n42\n");
+        assert_eq!(outcome, Outcome::Lost { attempts: 2, secret: 42 });
+        assert_eq!(transcript[transcript.len() - 3..], ["Too big!", "You lose!", "The secret number was 42."]);
+    }
+
+    #[test]
+    fn quitting_or_closing_the_input_abandons() {
+        let (outcome, transcript) = play_script(&quiet_config(), 42, "50\nquit\n");
+        assert_eq!(outcome, Outcome::Abandoned { attempts: 1 });
+        assert_eq!(transcript.last().unwrap(), "Bye! The secret number was 42.");
+
+        let (outcome, transcript) = play_script(&quiet_config(), 42, "");
+        assert_eq!(outcome, Outcome::Abandoned { attempts: 0 });
+        assert_eq!(transcript.last().unwrap(), "Goodbye!");
+    }
+
+    fn reverse(low: u32, high: u32, answers: &str) -> ReverseOutcome {
+        let mut config = GameConfig::for_difficulty(Difficulty::Custom);
+        config.low = low;
+        config.high = high;
+        play_reverse(&config, answers.as_bytes(), Vec::new()).unwrap()
+    }
+
+    #[test]
+    fn reverse_game_at_the_ends_of_u32() {
+        // 1..=100: 50, 75, 88, 94, 97, 99, 100
+        assert_eq!(reverse(1, 100, "h\nh\nh\nh\nh\nh\nc\n"), ReverseOutcome::Found { attempts: 7, number: 100 });
+        assert_eq!(reverse(u32::MAX, u32::MAX, "h\n"), ReverseOutcome::Contradiction { attempts: 1 });
+        assert_eq!(reverse(0, 0, "l\n"), ReverseOutcome::Contradiction { attempts: 1 });
+        assert_eq!(reverse(u32::MAX - 1, u32::MAX, "h\nh\n"), ReverseOutcome::Contradiction { attempts: 2 });
+        // 5 lower, 2 higher, 3 lower: nothing is left between 3 and 4
+        assert_eq!(reverse(1, 10, "l\nh\nl\n"), ReverseOutcome::Contradiction { attempts: 3 });
+    }
+
+    // joins `room`, sends START once `start_after` arrives, answers its own
+    // TURN (or an ERROR) with the next guess, and returns everything the
+    // server sent up to and including the WINNER line
+    fn bot(
+        addr: SocketAddr,
+        name: &str,
+        start_after: Option<&str>,
+        guesses: Vec<u32>,
+        joined: mpsc::Sender<()>,
+    ) -> Vec<String> {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+        let reader = BufReader::new(stream.try_clone().unwrap());
+        writeln!(stream, "JOIN {name} room").unwrap();
+        let mut guesses = guesses.into_iter();
+        let mut seen = Vec::new();
+        for line in reader.lines() {
+            let line = line.unwrap();
+            seen.push(line.clone());
+            if line.starts_with("WELCOME") {
+                joined.send(()).unwrap();
+            } else if Some(line.as_str()) == start_after {
+                writeln!(stream, "START").unwrap();
+            } else if line == format!("TURN {name}") || line.starts_with("ERROR") {
+                writeln!(stream, "GUESS {}", guesses.next().unwrap()).unwrap();
+            } else if line.starts_with("WINNER") {
                 break;
             }
         }
+        seen
+    }
+
+    #[test]
+    fn two_players_over_tcp() {
+        let mut config = GameConfig::for_difficulty(Difficulty::Easy);
+        config.seed = Some(7);
+        // the server draws from the same seeded rng
+        let secret = RandomSecret(StdRng::seed_from_u64(7)).secret(config.low, config.high);
+        let (wrong, feedback) = if secret == 1 { (2, "BIG") } else { (1, "SMALL") };
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        thread::spawn(move || serve(listener, config));
+
+        // bob only connects once alice is in, so the turn order is fixed
+        let (joined, alice_in) = mpsc::channel();
+        let alice = thread::spawn({
+            let joined = joined.clone();
+            move || bot(addr, "alice", Some("JOINED bob"), vec![11, wrong], joined)
+        });
+        alice_in.recv().unwrap();
+        let bob = thread::spawn(move || bot(addr, "bob", None, vec![secret], joined));
+
+        let result = format!("RESULT alice {wrong} {feedback}");
+        let winner = format!("WINNER bob {secret} 2");
+        assert_eq!(
+            alice.join().unwrap(),
+            [
+                "WELCOME room",
+                "JOINED bob",
+                "START 1 10",
+                "TURN alice",
+                "ERROR guess must be between 1 and 10",
+                &result,
+                "TURN bob",
+                &winner,
+            ]
+        );
+        assert_eq!(
+            bob.join().unwrap(),
+            ["WELCOME room alice", "START 1 10", "TURN alice", &result, "TURN bob", &winner]
+        );
+    }
+
+    #[test]
+    fn leaderboard_ranks_within_each_difficulty() {
+        colored::control::set_override(false);
+        let path = env::temp_dir().join(format!("guessing_game_scores_{}.toml", std::process::id()));
+        let store = ScoreStore::new(path.clone());
+        let win = |player: &str, difficulty, attempts| Score {
+            player: String::from(player),
+            difficulty,
+            won: true,
+            attempts,
+            duration: Duration::from_secs(10),
+        };
+        store.record(&win("ferris", Difficulty::Easy, 2)).unwrap();
+        store.record(&win("corro", Difficulty::Hard, 9)).unwrap();
+        store.record(&win("ferris", Difficulty::Hard, 7)).unwrap();
+
+        let players = |difficulty| -> Vec<String> {
+            store.leaderboard(difficulty, 10).unwrap().into_iter().map(|s| s.player).collect()
+        };
+        assert_eq!(players(Difficulty::Easy), ["ferris"]);
+        assert_eq!(players(Difficulty::Hard), ["ferris", "corro"]);
+        assert!(players(Difficulty::Normal).is_empty());
+
+        let mut output = Vec::new();
+        store.print_leaderboard(&mut output).unwrap();
+        fs::remove_file(&path).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        let lines: Vec<&str> = output.lines().take(6).collect();
+        assert_eq!(
+            lines,
+            [
+                "High scores",
+                "Easy",
+                " 1. ferris             2 attempts     10.0s",
+                "Hard",
+                " 1. ferris             7 attempts     10.0s",
+                " 2. corro              9 attempts     10.0s",
+            ]
+        );
+    }
+
+    #[test]
+    fn client_messages() {
+        colored::control::set_override(false);
+        assert_eq!(describe("RESULT alice 5 SMALL"), "alice guessed 5: Too small!");
+        assert_eq!(describe("WINNER bob 7 2"), "bob wins! The number was 7 (2 guesses in total).");
+        assert_eq!(describe("ERROR it is not your turn"), "it is not your turn");
+        assert_eq!(describe("ERROR"), "");
     }
 }
@@ -115,6 +115,44 @@ fn main() {
     // this works too, without the slice syntax!
     let word = first_word(my_string_literal);
     
+    //--------------zero-copy tokenizer--------------
+    // every piece handed out is a slice of the input, so nothing is allocated
+    // and the input can't be changed while the pieces are still in use
+    let text = String::from("Call me Ishmael.  Some\u{3000}years ago… never mind! How long?");
+    let words: Vec<&str> = tokenizer::words(&text).collect(); // splits on any unicode whitespace
+    println!("{:?}", words);
+    println!("{:?} {:?}", tokenizer::nth_word(&text, 2), tokenizer::last_word(&text));
+    println!("{:?}", tokenizer::first_word("\u{3000}Ishmael\u{a0}was here")); // "Ishmael"
+    println!("{:?}", tokenizer::words("a \u{301}b c").count()); // 2: a space carrying an accent isn't a separator
+    for sentence in tokenizer::sentences(&text) {
+        println!("{}", sentence); // "Call me Ishmael", "Some years ago… never mind", "How long"
+    }
+    // text.clear(); // ERROR: `words` still borrows from text
+    println!("{}", words.len());
+    
+    //--------------borrowing-safe text buffer--------------
+    // `change` and `takes_and_gives_back` as a reusable type: edits need `&mut`,
+    // slices borrow `&`, so the `s.clear()` mistake above can't compile
+    use text_buffer::{Rope, Text, TextBuffer};
+    let mut buffer = TextBuffer::from("hello").with(", world"); // takes and gives back
+    buffer.insert(5, " there").unwrap();
+    buffer.replace(0..5, "Здравствуй").unwrap();
+    println!("{} ({} chars, {} bytes)", buffer.as_str(), buffer.char_len(), buffer.byte_len());
+    let first = buffer.slice(0..10).unwrap();
+    // buffer.truncate(3); // ERROR: `first` still borrows buffer
+    println!("{}", first);
+    buffer.truncate(10).unwrap();
+    let owned: String = buffer.into_string(); // gives the String back
+    let mut empty = TextBuffer::new();
+    empty.insert(0, &owned).unwrap();
+    println!("{}", empty.as_str()); // Здравствуй
+    // the rope hands out its chunks instead of one contiguous slice
+    let mut rope = Rope::from(&"lorem ipsum ".repeat(1000)[..]);
+    rope.insert(6, "dolor ").unwrap();
+    println!("{} chunks, {} chars", rope.chunks().count(), rope.char_len());
+    let pieces: String = rope.slice(0..17).unwrap().collect(); // borrowed pieces, joined only here
+    println!("{}", pieces); // lorem dolor ipsum
+    
     //--------------array slicing--------------
     let a = [1, 2, 3, 4, 5];
     let slice = &a[1..3];
@@ -173,3 +211,465 @@ fn first_word(s: &str) -> &str {
     }
     &s[..]
 }
+
+mod tokenizer {
+    // a whitespace char followed by a combining mark (e.g. " \u{301}") is
+    // treated as part of a word rather than a separator, which keeps combining
+    // marks with their base letter; other grapheme clusters (emoji sequences,
+    // Hangul syllables, ...) are not handled
+    fn is_extend(c: char) -> bool {
+        matches!(c,
+            '\u{0300}'..='\u{036F}'   // combining diacritical marks
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}' // combining marks for symbols
+            | '\u{FE00}'..='\u{FE0F}' // variation selectors
+            | '\u{FE20}'..='\u{FE2F}'
+            | '\u{200C}' | '\u{200D}' // zero width (non-)joiner
+        )
+    }
+
+    // is the char `c` starting at byte `i` of `s` a word separator?
+    fn is_separator(s: &str, i: usize, c: char) -> bool {
+        c.is_whitespace() && !s[i + c.len_utf8()..].chars().next().is_some_and(is_extend)
+    }
+
+    pub struct Words<'a> {
+        rest: &'a str,
+    }
+
+    impl<'a> Iterator for Words<'a> {
+        type Item = &'a str;
+
+        fn next(&mut self) -> Option<&'a str> {
+            let s = self.rest;
+            let start = s.char_indices().find(|&(i, c)| !is_separator(s, i, c))?.0;
+            let end = s[start..]
+                .char_indices()
+                .find(|&(i, c)| is_separator(s, start + i, c))
+                .map_or(s.len(), |(i, _)| start + i);
+            self.rest = &s[end..];
+            Some(&s[start..end])
+        }
+    }
+
+    impl<'a> DoubleEndedIterator for Words<'a> {
+        fn next_back(&mut self) -> Option<&'a str> {
+            let s = self.rest;
+            let (last, c) = s.char_indices().rev().find(|&(i, c)| !is_separator(s, i, c))?;
+            let end = last + c.len_utf8();
+            let start = s[..end]
+                .char_indices()
+                .rev()
+                .find(|&(i, c)| is_separator(s, i, c))
+                .map_or(0, |(i, c)| i + c.len_utf8());
+            self.rest = &s[..start];
+            Some(&s[start..end])
+        }
+    }
+
+    pub fn words(s: &str) -> Words<'_> {
+        Words { rest: s }
+    }
+
+    // like `first_word`, but for any unicode whitespace
+    pub fn first_word(s: &str) -> &str {
+        words(s).next().unwrap_or(&s[..0])
+    }
+
+    pub fn nth_word(s: &str, n: usize) -> Option<&str> {
+        words(s).nth(n)
+    }
+
+    pub fn last_word(s: &str) -> Option<&str> {
+        words(s).next_back()
+    }
+
+    // the `ImportantExcerpt` split, generalised: sentences end at runs of
+    // '.', '!' or '?', and surrounding whitespace is trimmed off
+    pub struct Sentences<'a> {
+        rest: &'a str,
+    }
+
+    impl<'a> Iterator for Sentences<'a> {
+        type Item = &'a str;
+
+        fn next(&mut self) -> Option<&'a str> {
+            loop {
+                if self.rest.is_empty() {
+                    return None;
+                }
+                let s = self.rest;
+                let end = s.find(['.', '!', '?']).unwrap_or(s.len());
+                let after = s[end..]
+                    .find(|c: char| !matches!(c, '.' | '!' | '?'))
+                    .map_or(s.len(), |i| end + i);
+                self.rest = &s[after..];
+                let sentence = s[..end].trim();
+                if !sentence.is_empty() {
+                    return Some(sentence);
+                }
+            }
+        }
+    }
+
+    pub fn sentences(s: &str) -> Sentences<'_> {
+        Sentences { rest: s }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const SAMPLES: [&str; 8] = [
+            "",
+            "   ",
+            "one",
+            "  hello   world  ",
+            "tabs\tand\nnewlines\r\n too",
+            "ideographic\u{3000}space\u{00A0}and nbsp",
+            "cafe\u{301} au lait",
+            "a \u{301}b  \u{20DD} c",
+        ];
+
+        #[test]
+        fn backwards_matches_forwards() {
+            for s in SAMPLES {
+                let mut forwards: Vec<&str> = words(s).collect();
+                forwards.reverse();
+                assert_eq!(words(s).rev().collect::<Vec<_>>(), forwards, "{:?}", s);
+                assert_eq!(last_word(s), forwards.first().copied());
+            }
+        }
+
+        #[test]
+        fn both_ends_meet_in_the_middle() {
+            let mut it = words("a b c d e");
+            assert_eq!(it.next(), Some("a"));
+            assert_eq!(it.next_back(), Some("e"));
+            assert_eq!(it.next_back(), Some("d"));
+            assert_eq!(it.next(), Some("b"));
+            assert_eq!(it.next(), Some("c"));
+            assert_eq!(it.next_back(), None);
+            assert_eq!(it.next(), None);
+        }
+
+        #[test]
+        fn combining_marks_stay_with_their_letter() {
+            let words: Vec<&str> = words("cafe\u{301} au lait").collect();
+            assert_eq!(words, ["cafe\u{301}", "au", "lait"]);
+            // a mark after a space still belongs to the word, not the gap
+            assert_eq!(first_word("a \u{301}b c"), "a \u{301}b");
+            assert_eq!(last_word("x \u{301}"), Some("x \u{301}"));
+            // after a run of spaces only the last one goes with the mark, as a word of its own
+            assert_eq!(nth_word("x  \u{20DD} y", 1), Some(" \u{20DD}"));
+            assert_eq!(nth_word("x  \u{20DD} y", 2), Some("y"));
+        }
+    }
+}
+
+mod text_buffer {
+    use std::fmt;
+    use std::ops::Range;
+
+    // all positions are in chars, never bytes, so an edit can't land in the
+    // middle of a multi-byte character
+    #[derive(Debug, PartialEq)]
+    pub enum EditError {
+        OutOfBounds { index: usize, len: usize },
+        BadRange { start: usize, end: usize },
+    }
+
+    impl fmt::Display for EditError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                EditError::OutOfBounds { index, len } => {
+                    write!(f, "char index {} is out of bounds for text of {} chars", index, len)
+                }
+                EditError::BadRange { start, end } => write!(f, "range {}..{} is backwards", start, end),
+            }
+        }
+    }
+
+    impl std::error::Error for EditError {}
+
+    pub trait Text {
+        fn byte_len(&self) -> usize;
+        fn char_len(&self) -> usize;
+        fn append(&mut self, s: &str);
+        fn insert(&mut self, at: usize, s: &str) -> Result<(), EditError>;
+        fn remove(&mut self, range: Range<usize>) -> Result<(), EditError>;
+
+        fn replace(&mut self, range: Range<usize>, s: &str) -> Result<(), EditError> {
+            let start = range.start;
+            self.remove(range)?;
+            self.insert(start, s)
+        }
+
+        fn truncate(&mut self, chars: usize) -> Result<(), EditError> {
+            let len = self.char_len();
+            self.remove(chars..len)
+        }
+
+        // consuming version of `append`, like `takes_and_gives_back`
+        fn with(mut self, s: &str) -> Self
+        where
+            Self: Sized,
+        {
+            self.append(s);
+            self
+        }
+    }
+
+    fn check(range: &Range<usize>, len: usize) -> Result<(), EditError> {
+        if range.start > range.end {
+            return Err(EditError::BadRange { start: range.start, end: range.end });
+        }
+        if range.end > len {
+            return Err(EditError::OutOfBounds { index: range.end, len });
+        }
+        Ok(())
+    }
+
+    // byte offset of char `index` in `s`; `index` may be one past the end
+    fn byte_offset(s: &str, index: usize) -> usize {
+        s.char_indices().nth(index).map_or(s.len(), |(i, _)| i)
+    }
+
+    // a single contiguous String, with the char count cached so `char_len`
+    // doesn't have to walk the text
+    #[derive(Debug, Default, Clone, PartialEq)]
+    pub struct TextBuffer {
+        text: String,
+        chars: usize,
+    }
+
+    impl TextBuffer {
+        pub fn new() -> TextBuffer {
+            TextBuffer::default()
+        }
+
+        pub fn as_str(&self) -> &str {
+            &self.text
+        }
+
+        pub fn slice(&self, range: Range<usize>) -> Result<&str, EditError> {
+            check(&range, self.chars)?;
+            let start = byte_offset(&self.text, range.start);
+            let end = start + byte_offset(&self.text[start..], range.end - range.start);
+            Ok(&self.text[start..end])
+        }
+
+        pub fn into_string(self) -> String {
+            self.text
+        }
+    }
+
+    impl From<&str> for TextBuffer {
+        fn from(s: &str) -> TextBuffer {
+            TextBuffer { text: String::from(s), chars: s.chars().count() }
+        }
+    }
+
+    impl fmt::Display for TextBuffer {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str(&self.text)
+        }
+    }
+
+    impl Text for TextBuffer {
+        fn byte_len(&self) -> usize {
+            self.text.len()
+        }
+
+        fn char_len(&self) -> usize {
+            self.chars
+        }
+
+        fn append(&mut self, s: &str) {
+            self.text.push_str(s);
+            self.chars += s.chars().count();
+        }
+
+        fn insert(&mut self, at: usize, s: &str) -> Result<(), EditError> {
+            if at > self.chars {
+                return Err(EditError::OutOfBounds { index: at, len: self.chars });
+            }
+            let offset = byte_offset(&self.text, at);
+            self.text.insert_str(offset, s);
+            self.chars += s.chars().count();
+            Ok(())
+        }
+
+        fn remove(&mut self, range: Range<usize>) -> Result<(), EditError> {
+            check(&range, self.chars)?;
+            let start = byte_offset(&self.text, range.start);
+            let end = start + byte_offset(&self.text[start..], range.end - range.start);
+            self.text.replace_range(start..end, "");
+            self.chars -= range.end - range.start;
+            Ok(())
+        }
+    }
+
+    // for large documents: the text is kept in bounded chunks, so an edit only
+    // shifts the bytes of one chunk instead of the whole document
+    const CHUNK_BYTES: usize = 1024;
+
+    #[derive(Debug, Default, Clone)]
+    struct Chunk {
+        text: String,
+        chars: usize,
+    }
+
+    #[derive(Debug, Default, Clone)]
+    pub struct Rope {
+        chunks: Vec<Chunk>,
+        bytes: usize,
+        chars: usize,
+    }
+
+    impl Rope {
+        pub fn new() -> Rope {
+            Rope::default()
+        }
+
+        // the text in order, one borrowed piece per chunk
+        pub fn chunks(&self) -> impl Iterator<Item = &str> {
+            self.chunks.iter().map(|chunk| chunk.text.as_str())
+        }
+
+        // the pieces of the text that fall inside `range`
+        pub fn slice(&self, range: Range<usize>) -> Result<impl Iterator<Item = &str>, EditError> {
+            check(&range, self.chars)?;
+            let mut skip = range.start;
+            let mut take = range.end - range.start;
+            Ok(self.chunks.iter().filter_map(move |chunk| {
+                if skip >= chunk.chars {
+                    skip -= chunk.chars;
+                    return None;
+                }
+                if take == 0 {
+                    return None;
+                }
+                let n = take.min(chunk.chars - skip);
+                let start = byte_offset(&chunk.text, skip);
+                let end = start + byte_offset(&chunk.text[start..], n);
+                skip = 0;
+                take -= n;
+                Some(&chunk.text[start..end])
+            }))
+        }
+
+        // index of the chunk holding char `at`, and the char offset inside it
+        fn locate(&self, mut at: usize) -> (usize, usize) {
+            for (i, chunk) in self.chunks.iter().enumerate() {
+                if at <= chunk.chars {
+                    return (i, at);
+                }
+                at -= chunk.chars;
+            }
+            (self.chunks.len(), 0)
+        }
+
+        // splits an oversized chunk into pieces of at most CHUNK_BYTES,
+        // always on a char boundary
+        fn split_chunk(&mut self, i: usize) {
+            if self.chunks[i].text.len() <= CHUNK_BYTES {
+                return;
+            }
+            let text = std::mem::take(&mut self.chunks[i].text);
+            let mut pieces = Vec::new();
+            let mut rest = text.as_str();
+            while !rest.is_empty() {
+                let mut end = rest.len().min(CHUNK_BYTES);
+                while !rest.is_char_boundary(end) {
+                    end -= 1;
+                }
+                let piece = &rest[..end];
+                pieces.push(Chunk { text: String::from(piece), chars: piece.chars().count() });
+                rest = &rest[end..];
+            }
+            self.chunks.splice(i..=i, pieces);
+        }
+    }
+
+    impl From<&str> for Rope {
+        fn from(s: &str) -> Rope {
+            let mut rope = Rope::new();
+            rope.append(s);
+            rope
+        }
+    }
+
+    impl fmt::Display for Rope {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            for chunk in self.chunks() {
+                f.write_str(chunk)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Text for Rope {
+        fn byte_len(&self) -> usize {
+            self.bytes
+        }
+
+        fn char_len(&self) -> usize {
+            self.chars
+        }
+
+        fn append(&mut self, s: &str) {
+            let at = self.chars;
+            self.insert(at, s).expect("appending is always in bounds");
+        }
+
+        fn insert(&mut self, at: usize, s: &str) -> Result<(), EditError> {
+            if at > self.chars {
+                return Err(EditError::OutOfBounds { index: at, len: self.chars });
+            }
+            if s.is_empty() {
+                return Ok(());
+            }
+            if self.chunks.is_empty() {
+                self.chunks.push(Chunk::default());
+            }
+            let (i, offset) = self.locate(at);
+            let chunk = &mut self.chunks[i];
+            let byte = byte_offset(&chunk.text, offset);
+            chunk.text.insert_str(byte, s);
+            let chars = s.chars().count();
+            chunk.chars += chars;
+            self.chars += chars;
+            self.bytes += s.len();
+            self.split_chunk(i);
+            Ok(())
+        }
+
+        fn remove(&mut self, range: Range<usize>) -> Result<(), EditError> {
+            check(&range, self.chars)?;
+            let mut skip = range.start;
+            let mut take = range.end - range.start;
+            for chunk in &mut self.chunks {
+                if take == 0 {
+                    break;
+                }
+                if skip >= chunk.chars {
+                    skip -= chunk.chars;
+                    continue;
+                }
+                let n = take.min(chunk.chars - skip);
+                let start = byte_offset(&chunk.text, skip);
+                let end = start + byte_offset(&chunk.text[start..], n);
+                chunk.text.replace_range(start..end, "");
+                chunk.chars -= n;
+                self.chars -= n;
+                self.bytes -= end - start;
+                skip = 0;
+                take -= n;
+            }
+            self.chunks.retain(|chunk| !chunk.text.is_empty());
+            Ok(())
+        }
+    }
+}
@@ -97,6 +97,240 @@ fn main() {
     }
 }
     
+    //--------------geometry--------------
+    // positioned shapes for layout maths; `Rect` is `Rectangle1` with a position
+    use geometry::{Circle, Point as GeoPoint, Polygon, Rect, Shape, Triangle};
+    impl From<&Rectangle1> for Rect {
+        fn from(r: &Rectangle1) -> Rect {
+            Rect::new(0.0, 0.0, r.width as f64, r.height as f64)
+        }
+    }
+    let page = Rect::from(&rect1);
+    let header = Rect::new(0.0, 0.0, 30.0, 10.0);
+    let sidebar = Rect::new(20.0, 5.0, 10.0, 45.0);
+    println!("{:?}", header.intersection(&sidebar)); // Some(Rect { x: 20.0, y: 5.0, width: 10.0, height: 5.0 })
+    println!("{}", header.overlap_area(&sidebar)); // 50
+    println!("{}", page.contains(&header)); // true: edges may touch
+    println!("{}", page.contains_strictly(&header)); // false: like can_hold, edges may not
+    println!("{} {}", page.can_hold(&header), header.intersects(&sidebar)); // false true
+    let (top, bottom) = page.split_horizontal(10.0);
+    println!("{:?} {:?} {:?}", top, bottom, top.union(&bottom) == page);
+    let (left, right) = page.split_vertical(10.0);
+    println!("{:?} {:?}", left, right);
+    let mut shapes: Vec<Box<dyn Shape>> = vec![
+        Box::new(page.scaled(0.5)),
+        Box::new(Circle::new(GeoPoint::new(5.0, 5.0), 3.0)),
+        Box::new(Triangle::new(GeoPoint::new(0.0, 0.0), GeoPoint::new(4.0, 0.0), GeoPoint::new(0.0, 3.0))),
+        Box::new(Polygon::new(vec![GeoPoint::new(0.0, 0.0), GeoPoint::new(2.0, 0.0), GeoPoint::new(2.0, 2.0), GeoPoint::new(0.0, 2.0)])),
+    ];
+    for shape in &shapes {
+        println!("area {:.2}, perimeter {:.2}, bounds {:?}", shape.area(), shape.perimeter(), shape.bounding_box());
+    }
+    for shape in &mut shapes {
+        shape.translate(1.0, 1.0);
+        println!("contains (1.5, 1.5) after moving: {}", shape.contains_point(&GeoPoint::new(1.5, 1.5)));
+    }
+    
+    //--------------bin packing--------------
+    // `can_hold` answers yes/no for one rectangle; a packer places many of them
+    use packing::{Algorithm, Item, Packer};
+    let sprites = vec![
+        Item::new(0, 30, 20),
+        Item::new(1, 50, 10),
+        Item::new(2, 20, 20),
+        Item::new(3, 10, 45),
+        Item::new(4, 90, 90), // too big for any sheet
+    ];
+    for algorithm in [Algorithm::Shelf, Algorithm::Guillotine, Algorithm::MaxRects] {
+        let packing = Packer::new(64, 64, algorithm).rotation(true).pack(&sprites);
+        println!(
+            "{:?}: {} sheets, {:.0}% used, unplaced {:?}",
+            algorithm,
+            packing.bins,
+            packing.utilisation() * 100.0,
+            packing.unplaced
+        );
+        for p in &packing.placements {
+            println!("  sprite {} -> sheet {} at ({}, {}){}", p.id, p.bin, p.x, p.y, if p.rotated { " rotated" } else { "" });
+        }
+    }
+    // smaller sheets and at most two of them: what doesn't fit is reported, not packed
+    let capped = Packer::new(40, 40, Algorithm::MaxRects).max_bins(2).pack(&sprites);
+    for bin in 0..capped.bins {
+        println!("sheet {}: {:.0}% used", bin, capped.bin_utilisation(bin) * 100.0);
+    }
+    println!("unplaced {:?}", capped.unplaced);
+    
+    //--------------user registry--------------
+    // `build_user` with validation, unique usernames and a sign-in history
+    use accounts::Registry;
+    let mut registry = Registry::new();
+    registry.register("someusername123", "someone@example.com").unwrap();
+    println!("{:?}", registry.register("SomeUsername123", "other@example.com")); // Err(UsernameTaken(..))
+    println!("{:?}", registry.register("x", "not-an-email")); // Err(InvalidUsername(..))
+    registry.sign_in("someusername123").unwrap();
+    registry.deactivate("someusername123").unwrap();
+    println!("{:?}", registry.sign_in("someusername123")); // Err(Inactive(..))
+    registry.reactivate("someusername123").unwrap();
+    println!("{:?}", registry.change_email("someusername123", "no-at-sign")); // Err(InvalidEmail(..))
+    registry.change_email("someusername123", "someone@example.org").unwrap();
+    let path = std::env::temp_dir().join("users.json");
+    registry.save(&path).unwrap();
+    let registry = Registry::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    println!("{:#?}", registry.get("someusername123"));
+    println!("{} users", registry.users().count());
+    
+    //--------------builder--------------
+    // `..user2` above moves username out of user2; these helpers avoid that
+    // typestate: `build` only exists once both username and email are set
+    struct Missing;
+    struct Set<T>(T);
+    struct UserBuilder<U, E> {
+        username: U,
+        email: E,
+        active: bool,
+        sign_in_count: u64,
+    }
+    impl UserBuilder<Missing, Missing> {
+        fn new() -> UserBuilder<Missing, Missing> {
+            UserBuilder { username: Missing, email: Missing, active: true, sign_in_count: 1 }
+        }
+    }
+    impl<E> UserBuilder<Missing, E> {
+        fn username(self, username: &str) -> UserBuilder<Set<String>, E> {
+            UserBuilder {
+                username: Set(String::from(username)),
+                email: self.email,
+                active: self.active,
+                sign_in_count: self.sign_in_count,
+            }
+        }
+    }
+    impl<U> UserBuilder<U, Missing> {
+        fn email(self, email: &str) -> UserBuilder<U, Set<String>> {
+            UserBuilder {
+                username: self.username,
+                email: Set(String::from(email)),
+                active: self.active,
+                sign_in_count: self.sign_in_count,
+            }
+        }
+    }
+    impl<U, E> UserBuilder<U, E> {
+        fn active(mut self, active: bool) -> Self {
+            self.active = active;
+            self
+        }
+        fn sign_in_count(mut self, sign_in_count: u64) -> Self {
+            self.sign_in_count = sign_in_count;
+            self
+        }
+    }
+    impl UserBuilder<Set<String>, Set<String>> {
+        fn build(self) -> User {
+            User {
+                active: self.active,
+                username: self.username.0,
+                email: self.email.0,
+                sign_in_count: self.sign_in_count,
+            }
+        }
+    }
+    let user4 = UserBuilder::new().username("someusername123").email("someone@example.com").build();
+    let dormant = UserBuilder::new().email("dormant@example.com").active(false).sign_in_count(0).username("dormant").build();
+    println!("{} active: {}, signed in {} times", dormant.username, dormant.active, dormant.sign_in_count);
+    // neither of these compiles (E0599: no method named `build` found):
+    // let user5 = UserBuilder::new().username("nobody").build(); // email is missing
+    // let user5 = UserBuilder::new().email("nobody@example.com").active(false).build(); // username is missing
+    // non-consuming copies: the original stays usable
+    impl User {
+        fn with_email(&self, email: &str) -> User {
+            User {
+                email: String::from(email),
+                username: self.username.clone(),
+                ..*self // only the Copy fields are left, so nothing is moved
+            }
+        }
+        fn with_username(&self, username: &str) -> User {
+            User {
+                username: String::from(username),
+                email: self.email.clone(),
+                ..*self
+            }
+        }
+    }
+    let user5 = user4.with_email("another@example.com");
+    let user6 = user4.with_username("anotherusername456").with_email("third@example.com");
+    println!("{} {} {}", user4.email, user5.email, user6.username); // user4 is still valid
+    
+    // generated builders for any struct: `= value` marks an optional field
+    // with a default; the rest are required and checked when `build` runs
+    macro_rules! builder {
+        ($name:ident => $builder:ident { $($field:ident : $ty:ty $(= $default:expr)?),* $(,)? }) => {
+            #[derive(Default)]
+            struct $builder {
+                $($field: Option<$ty>,)*
+            }
+            impl $builder {
+                $(
+                    fn $field(mut self, value: $ty) -> Self {
+                        self.$field = Some(value);
+                        self
+                    }
+                )*
+                fn build(self) -> Result<$name, String> {
+                    Ok($name {
+                        $($field: match self.$field {
+                            Some(value) => value,
+                            None => builder!(@default $field $($default)?),
+                        },)*
+                    })
+                }
+            }
+            impl $name {
+                fn builder() -> $builder {
+                    $builder::default()
+                }
+            }
+        };
+        (@default $field:ident $default:expr) => { $default };
+        (@default $field:ident) => {
+            return Err(format!("missing required field `{}`", stringify!($field)))
+        };
+    }
+    builder!(Rectangle => RectangleBuilder { width: u32, height: u32 = 1 });
+    let rect5 = Rectangle::builder().width(30).build(); // Ok(Rectangle { width: 30, height: 1 })
+    let rect6 = Rectangle::builder().width(30).height(50).build(); // Ok(Rectangle { width: 30, height: 50 })
+    println!("{:?} {:?}", rect5, rect6);
+    println!("{:?}", Rectangle::builder().height(50).build().err()); // Some("missing required field `width`")
+    #[derive(Debug)]
+    struct NewsArticle { // as in tut10_traits.rs
+        headline: String,
+        location: String,
+        author: String,
+        content: String,
+    }
+    builder!(NewsArticle => NewsArticleBuilder {
+        headline: String,
+        location: String = String::from("Unknown"),
+        author: String,
+        content: String = String::new(),
+    });
+    let article = NewsArticle::builder()
+        .headline(String::from("Penguins win the Stanley Cup Championship!"))
+        .author(String::from("Iceburgh"))
+        .build()
+        .unwrap();
+    println!("{}, by {} ({}): {:?}", article.headline, article.author, article.location, article.content); // defaults filled in
+    let article = NewsArticle::builder()
+        .headline(String::from("Penguins win the Stanley Cup Championship!"))
+        .location(String::from("Pittsburgh, PA, USA"))
+        .author(String::from("Iceburgh"))
+        .content(String::from("The Pittsburgh Penguins once again are the best hockey team in the NHL."))
+        .build();
+    println!("{:?}", article);
+    println!("{:?}", NewsArticle::builder().build().err()); // Some("missing required field `headline`")
     
     // functions
     fn build_user(email: String, username: String) -> User {
@@ -117,3 +351,1051 @@ fn main() {
     }
 }
 
+
+mod geometry {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Point {
+        pub x: f64,
+        pub y: f64,
+    }
+
+    impl Point {
+        pub fn new(x: f64, y: f64) -> Point {
+            Point { x, y }
+        }
+
+        pub fn distance(&self, other: &Point) -> f64 {
+            ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt()
+        }
+    }
+
+    // scaling is always about the origin, so a layout scaled as a whole keeps
+    // its shapes in the same relative positions
+    pub trait Shape {
+        fn area(&self) -> f64;
+        fn perimeter(&self) -> f64;
+        fn bounding_box(&self) -> Rect;
+        fn contains_point(&self, p: &Point) -> bool;
+        fn translate(&mut self, dx: f64, dy: f64);
+        fn scale(&mut self, factor: f64);
+    }
+
+    // x grows to the right and y grows downwards, as on a screen
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Rect {
+        pub x: f64,
+        pub y: f64,
+        pub width: f64,
+        pub height: f64,
+    }
+
+    impl Rect {
+        pub fn new(x: f64, y: f64, width: f64, height: f64) -> Rect {
+            Rect { x, y, width, height }
+        }
+
+        pub fn square(x: f64, y: f64, size: f64) -> Rect {
+            Rect::new(x, y, size, size)
+        }
+
+        pub fn right(&self) -> f64 {
+            self.x + self.width
+        }
+
+        pub fn bottom(&self) -> f64 {
+            self.y + self.height
+        }
+
+        // `Rectangle1::can_hold`: compares sizes only, ignoring position
+        pub fn can_hold(&self, other: &Rect) -> bool {
+            self.width > other.width && self.height > other.height
+        }
+
+        // `other` lies inside, edges may touch
+        pub fn contains(&self, other: &Rect) -> bool {
+            other.x >= self.x && other.y >= self.y && other.right() <= self.right() && other.bottom() <= self.bottom()
+        }
+
+        // `other` lies inside without touching any edge
+        pub fn contains_strictly(&self, other: &Rect) -> bool {
+            other.x > self.x && other.y > self.y && other.right() < self.right() && other.bottom() < self.bottom()
+        }
+
+        // rectangles that only share an edge do not intersect
+        pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+            let x = self.x.max(other.x);
+            let y = self.y.max(other.y);
+            let right = self.right().min(other.right());
+            let bottom = self.bottom().min(other.bottom());
+            if right > x && bottom > y {
+                Some(Rect::new(x, y, right - x, bottom - y))
+            } else {
+                None
+            }
+        }
+
+        pub fn intersects(&self, other: &Rect) -> bool {
+            self.intersection(other).is_some()
+        }
+
+        pub fn overlap_area(&self, other: &Rect) -> f64 {
+            self.intersection(other).map_or(0.0, |r| r.area())
+        }
+
+        // the smallest rectangle covering both
+        pub fn union(&self, other: &Rect) -> Rect {
+            let x = self.x.min(other.x);
+            let y = self.y.min(other.y);
+            Rect::new(x, y, self.right().max(other.right()) - x, self.bottom().max(other.bottom()) - y)
+        }
+
+        pub fn scaled(&self, factor: f64) -> Rect {
+            let mut r = *self;
+            r.scale(factor);
+            r
+        }
+
+        // cuts `at` units from the top; `at` is clamped to the height
+        pub fn split_horizontal(&self, at: f64) -> (Rect, Rect) {
+            let at = at.clamp(0.0, self.height);
+            (
+                Rect::new(self.x, self.y, self.width, at),
+                Rect::new(self.x, self.y + at, self.width, self.height - at),
+            )
+        }
+
+        // cuts `at` units from the left; `at` is clamped to the width
+        pub fn split_vertical(&self, at: f64) -> (Rect, Rect) {
+            let at = at.clamp(0.0, self.width);
+            (
+                Rect::new(self.x, self.y, at, self.height),
+                Rect::new(self.x + at, self.y, self.width - at, self.height),
+            )
+        }
+    }
+
+    impl Shape for Rect {
+        fn area(&self) -> f64 {
+            self.width * self.height
+        }
+
+        fn perimeter(&self) -> f64 {
+            2.0 * (self.width + self.height)
+        }
+
+        fn bounding_box(&self) -> Rect {
+            *self
+        }
+
+        fn contains_point(&self, p: &Point) -> bool {
+            p.x >= self.x && p.x <= self.right() && p.y >= self.y && p.y <= self.bottom()
+        }
+
+        fn translate(&mut self, dx: f64, dy: f64) {
+            self.x += dx;
+            self.y += dy;
+        }
+
+        fn scale(&mut self, factor: f64) {
+            self.x *= factor;
+            self.y *= factor;
+            self.width *= factor;
+            self.height *= factor;
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Circle {
+        pub center: Point,
+        pub radius: f64,
+    }
+
+    impl Circle {
+        pub fn new(center: Point, radius: f64) -> Circle {
+            Circle { center, radius }
+        }
+    }
+
+    impl Shape for Circle {
+        fn area(&self) -> f64 {
+            std::f64::consts::PI * self.radius * self.radius
+        }
+
+        fn perimeter(&self) -> f64 {
+            2.0 * std::f64::consts::PI * self.radius
+        }
+
+        fn bounding_box(&self) -> Rect {
+            Rect::square(self.center.x - self.radius, self.center.y - self.radius, 2.0 * self.radius)
+        }
+
+        fn contains_point(&self, p: &Point) -> bool {
+            self.center.distance(p) <= self.radius
+        }
+
+        fn translate(&mut self, dx: f64, dy: f64) {
+            self.center.x += dx;
+            self.center.y += dy;
+        }
+
+        fn scale(&mut self, factor: f64) {
+            self.center.x *= factor;
+            self.center.y *= factor;
+            self.radius *= factor;
+        }
+    }
+
+    // any simple (non self-intersecting) polygon, vertices in order
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Polygon {
+        pub points: Vec<Point>,
+    }
+
+    impl Polygon {
+        pub fn new(points: Vec<Point>) -> Polygon {
+            Polygon { points }
+        }
+
+        // each edge as (from, to), including the closing edge
+        fn edges(&self) -> impl Iterator<Item = (&Point, &Point)> {
+            self.points.iter().zip(self.points.iter().cycle().skip(1))
+        }
+    }
+
+    impl Shape for Polygon {
+        // shoelace formula
+        fn area(&self) -> f64 {
+            let twice: f64 = self.edges().map(|(a, b)| a.x * b.y - b.x * a.y).sum();
+            twice.abs() / 2.0
+        }
+
+        fn perimeter(&self) -> f64 {
+            self.edges().map(|(a, b)| a.distance(b)).sum()
+        }
+
+        fn bounding_box(&self) -> Rect {
+            let first = match self.points.first() {
+                Some(p) => *p,
+                None => return Rect::new(0.0, 0.0, 0.0, 0.0),
+            };
+            let (mut min, mut max) = (first, first);
+            for p in &self.points {
+                min = Point::new(min.x.min(p.x), min.y.min(p.y));
+                max = Point::new(max.x.max(p.x), max.y.max(p.y));
+            }
+            Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+        }
+
+        // ray casting: count the edges a ray to the right of `p` crosses
+        fn contains_point(&self, p: &Point) -> bool {
+            let mut inside = false;
+            for (a, b) in self.edges() {
+                if (a.y > p.y) != (b.y > p.y) && p.x < (b.x - a.x) * (p.y - a.y) / (b.y - a.y) + a.x {
+                    inside = !inside;
+                }
+            }
+            inside
+        }
+
+        fn translate(&mut self, dx: f64, dy: f64) {
+            for p in &mut self.points {
+                p.x += dx;
+                p.y += dy;
+            }
+        }
+
+        fn scale(&mut self, factor: f64) {
+            for p in &mut self.points {
+                p.x *= factor;
+                p.y *= factor;
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Triangle(Polygon);
+
+    impl Triangle {
+        pub fn new(a: Point, b: Point, c: Point) -> Triangle {
+            Triangle(Polygon::new(vec![a, b, c]))
+        }
+    }
+
+    impl Shape for Triangle {
+        fn area(&self) -> f64 {
+            self.0.area()
+        }
+
+        fn perimeter(&self) -> f64 {
+            self.0.perimeter()
+        }
+
+        fn bounding_box(&self) -> Rect {
+            self.0.bounding_box()
+        }
+
+        fn contains_point(&self, p: &Point) -> bool {
+            self.0.contains_point(p)
+        }
+
+        fn translate(&mut self, dx: f64, dy: f64) {
+            self.0.translate(dx, dy)
+        }
+
+        fn scale(&mut self, factor: f64) {
+            self.0.scale(factor)
+        }
+    }
+}
+
+mod packing {
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Item {
+        pub id: usize,
+        pub width: u32,
+        pub height: u32,
+    }
+
+    impl Item {
+        pub fn new(id: usize, width: u32, height: u32) -> Item {
+            Item { id, width, height }
+        }
+    }
+
+    // width and height are as placed, i.e. already swapped when rotated
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Placement {
+        pub id: usize,
+        pub bin: usize,
+        pub x: u32,
+        pub y: u32,
+        pub width: u32,
+        pub height: u32,
+        pub rotated: bool,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Algorithm {
+        Shelf,      // rows of items, fast but wasteful with mixed heights
+        Guillotine, // splits the free space with edge-to-edge cuts
+        MaxRects,   // tracks every maximal free rectangle, usually the tightest
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Packing {
+        pub placements: Vec<Placement>,
+        pub unplaced: Vec<usize>, // ids of items that did not fit
+        pub bins: usize,
+        pub bin_width: u32,
+        pub bin_height: u32,
+    }
+
+    impl Packing {
+        // placed area over the area of all bins that were opened
+        pub fn utilisation(&self) -> f64 {
+            if self.bins == 0 {
+                return 0.0;
+            }
+            let used: u64 = self.placements.iter().map(|p| p.width as u64 * p.height as u64).sum();
+            used as f64 / (self.bins as u64 * self.bin_width as u64 * self.bin_height as u64) as f64
+        }
+
+        pub fn bin_utilisation(&self, bin: usize) -> f64 {
+            let used: u64 = self
+                .placements
+                .iter()
+                .filter(|p| p.bin == bin)
+                .map(|p| p.width as u64 * p.height as u64)
+                .sum();
+            used as f64 / (self.bin_width as u64 * self.bin_height as u64) as f64
+        }
+    }
+
+    pub struct Packer {
+        width: u32,
+        height: u32,
+        algorithm: Algorithm,
+        rotation: bool,
+        max_bins: Option<usize>,
+    }
+
+    impl Packer {
+        pub fn new(width: u32, height: u32, algorithm: Algorithm) -> Packer {
+            Packer { width, height, algorithm, rotation: false, max_bins: None }
+        }
+
+        // allow items to be turned 90 degrees
+        pub fn rotation(mut self, allow: bool) -> Packer {
+            self.rotation = allow;
+            self
+        }
+
+        // by default a new bin is opened whenever an item fits nowhere else
+        pub fn max_bins(mut self, max: usize) -> Packer {
+            self.max_bins = Some(max);
+            self
+        }
+
+        fn fits(&self, w: u32, h: u32) -> bool {
+            (w <= self.width && h <= self.height) || (self.rotation && h <= self.width && w <= self.height)
+        }
+
+        fn new_bin(&self) -> Box<dyn Bin> {
+            let free = Free { x: 0, y: 0, w: self.width, h: self.height };
+            match self.algorithm {
+                Algorithm::Shelf => Box::new(ShelfBin { width: self.width, height: self.height, shelves: Vec::new() }),
+                Algorithm::Guillotine => Box::new(GuillotineBin { free: vec![free] }),
+                Algorithm::MaxRects => Box::new(MaxRectsBin { free: vec![free] }),
+            }
+        }
+
+        pub fn pack(&self, items: &[Item]) -> Packing {
+            // big items first leaves the gaps for the small ones
+            let mut order: Vec<&Item> = items.iter().collect();
+            order.sort_by_key(|item| std::cmp::Reverse((item.width.max(item.height), item.width as u64 * item.height as u64)));
+
+            let mut bins: Vec<Box<dyn Bin>> = Vec::new();
+            let mut packing = Packing {
+                placements: Vec::new(),
+                unplaced: Vec::new(),
+                bins: 0,
+                bin_width: self.width,
+                bin_height: self.height,
+            };
+            for item in order {
+                if item.width == 0 || item.height == 0 || !self.fits(item.width, item.height) {
+                    packing.unplaced.push(item.id);
+                    continue;
+                }
+                let mut placed = None;
+                for (i, bin) in bins.iter_mut().enumerate() {
+                    if let Some(spot) = bin.insert(item.width, item.height, self.rotation) {
+                        placed = Some((i, spot));
+                        break;
+                    }
+                }
+                if placed.is_none() && self.max_bins.is_none_or(|max| bins.len() < max) {
+                    let mut bin = self.new_bin();
+                    placed = bin.insert(item.width, item.height, self.rotation).map(|spot| (bins.len(), spot));
+                    bins.push(bin);
+                }
+                match placed {
+                    Some((bin, (x, y, rotated))) => {
+                        let (width, height) = if rotated { (item.height, item.width) } else { (item.width, item.height) };
+                        packing.placements.push(Placement { id: item.id, bin, x, y, width, height, rotated });
+                    }
+                    None => packing.unplaced.push(item.id),
+                }
+            }
+            packing.bins = bins.len();
+            packing
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    struct Free {
+        x: u32,
+        y: u32,
+        w: u32,
+        h: u32,
+    }
+
+    impl Free {
+        // the `can_hold` check, but inclusive: an exact fit is fine here
+        fn can_hold(&self, w: u32, h: u32) -> bool {
+            w <= self.w && h <= self.h
+        }
+
+        fn contains(&self, other: &Free) -> bool {
+            other.x >= self.x && other.y >= self.y && other.x + other.w <= self.x + self.w && other.y + other.h <= self.y + self.h
+        }
+    }
+
+    // the orientations worth trying for an item
+    fn orientations(w: u32, h: u32, rotation: bool) -> Vec<(u32, u32, bool)> {
+        if rotation && w != h {
+            vec![(w, h, false), (h, w, true)]
+        } else {
+            vec![(w, h, false)]
+        }
+    }
+
+    trait Bin {
+        // returns the top-left corner and whether the item was rotated
+        fn insert(&mut self, w: u32, h: u32, rotation: bool) -> Option<(u32, u32, bool)>;
+    }
+
+    struct Shelf {
+        y: u32,
+        height: u32,
+        used: u32,
+    }
+
+    struct ShelfBin {
+        width: u32,
+        height: u32,
+        shelves: Vec<Shelf>,
+    }
+
+    impl Bin for ShelfBin {
+        fn insert(&mut self, w: u32, h: u32, rotation: bool) -> Option<(u32, u32, bool)> {
+            // the existing shelf that wastes the least height
+            let mut best: Option<(usize, u32, u32, bool, u32)> = None;
+            for (i, shelf) in self.shelves.iter().enumerate() {
+                for (w, h, rotated) in orientations(w, h, rotation) {
+                    if h <= shelf.height && shelf.used + w <= self.width {
+                        let waste = shelf.height - h;
+                        if best.is_none_or(|b| waste < b.4) {
+                            best = Some((i, w, h, rotated, waste));
+                        }
+                    }
+                }
+            }
+            if let Some((i, w, _, rotated, _)) = best {
+                let shelf = &mut self.shelves[i];
+                let x = shelf.used;
+                shelf.used += w;
+                return Some((x, shelf.y, rotated));
+            }
+
+            // otherwise open a new shelf, lying the item flat to keep it low
+            let top = self.shelves.last().map_or(0, |s| s.y + s.height);
+            let mut options = orientations(w, h, rotation);
+            options.sort_by_key(|&(_, h, _)| h);
+            let (w, h, rotated) = options.into_iter().find(|&(w, h, _)| w <= self.width && top + h <= self.height)?;
+            self.shelves.push(Shelf { y: top, height: h, used: w });
+            Some((0, top, rotated))
+        }
+    }
+
+    struct GuillotineBin {
+        free: Vec<Free>,
+    }
+
+    impl Bin for GuillotineBin {
+        fn insert(&mut self, w: u32, h: u32, rotation: bool) -> Option<(u32, u32, bool)> {
+            // best area fit
+            let mut best: Option<(usize, u32, u32, bool, u64)> = None;
+            for (i, free) in self.free.iter().enumerate() {
+                for (w, h, rotated) in orientations(w, h, rotation) {
+                    if !free.can_hold(w, h) {
+                        continue;
+                    }
+                    let waste = free.w as u64 * free.h as u64 - w as u64 * h as u64;
+                    if best.is_none_or(|b| waste < b.4) {
+                        best = Some((i, w, h, rotated, waste));
+                    }
+                }
+            }
+            let (i, w, h, rotated, _) = best?;
+            let free = self.free.swap_remove(i);
+
+            // cut along the shorter leftover side so the bigger piece stays whole
+            let (right, bottom) = if free.w - w < free.h - h {
+                (Free { x: free.x + w, y: free.y, w: free.w - w, h }, Free { x: free.x, y: free.y + h, w: free.w, h: free.h - h })
+            } else {
+                (Free { x: free.x + w, y: free.y, w: free.w - w, h: free.h }, Free { x: free.x, y: free.y + h, w, h: free.h - h })
+            };
+            self.free.extend([right, bottom].into_iter().filter(|r| r.w > 0 && r.h > 0));
+            Some((free.x, free.y, rotated))
+        }
+    }
+
+    struct MaxRectsBin {
+        free: Vec<Free>,
+    }
+
+    impl Bin for MaxRectsBin {
+        fn insert(&mut self, w: u32, h: u32, rotation: bool) -> Option<(u32, u32, bool)> {
+            // best short side fit
+            let mut best: Option<(Free, bool, u32)> = None;
+            for free in &self.free {
+                for (w, h, rotated) in orientations(w, h, rotation) {
+                    if free.can_hold(w, h) {
+                        let short_side = (free.w - w).min(free.h - h);
+                        if best.is_none_or(|b| short_side < b.2) {
+                            best = Some((Free { x: free.x, y: free.y, w, h }, rotated, short_side));
+                        }
+                    }
+                }
+            }
+            let (used, rotated, _) = best?;
+
+            // every free rectangle overlapping the new item is replaced by the
+            // (up to four) maximal rectangles around it
+            let mut next = Vec::new();
+            for free in self.free.drain(..) {
+                let overlaps = used.x < free.x + free.w && used.x + used.w > free.x && used.y < free.y + free.h && used.y + used.h > free.y;
+                if !overlaps {
+                    next.push(free);
+                    continue;
+                }
+                if used.x > free.x {
+                    next.push(Free { w: used.x - free.x, ..free });
+                }
+                if used.x + used.w < free.x + free.w {
+                    next.push(Free { x: used.x + used.w, w: free.x + free.w - used.x - used.w, ..free });
+                }
+                if used.y > free.y {
+                    next.push(Free { h: used.y - free.y, ..free });
+                }
+                if used.y + used.h < free.y + free.h {
+                    next.push(Free { y: used.y + used.h, h: free.y + free.h - used.y - used.h, ..free });
+                }
+            }
+            // drop rectangles that are inside another one
+            let mut pruned: Vec<Free> = Vec::new();
+            for (i, free) in next.iter().enumerate() {
+                let redundant = next
+                    .iter()
+                    .enumerate()
+                    .any(|(j, other)| i != j && other.contains(free) && (other != free || j < i));
+                if !redundant {
+                    pruned.push(*free);
+                }
+            }
+            self.free = pruned;
+            Some((used.x, used.y, rotated))
+        }
+    }
+}
+
+mod accounts {
+    use std::collections::HashMap;
+    use std::fmt;
+    use std::fs;
+    use std::io;
+    use std::path::Path;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct User {
+        pub active: bool,
+        pub username: String,
+        pub email: String,
+        pub sign_in_count: u64,
+        pub created_at: u64,   // seconds since the unix epoch
+        pub sign_ins: Vec<u64>, // when each sign-in happened
+    }
+
+    #[derive(Debug)]
+    pub enum AccountError {
+        InvalidUsername(String),
+        InvalidEmail(String),
+        UsernameTaken(String),
+        UnknownUser(String),
+        Inactive(String),
+        Io(io::Error),
+        Corrupt(String), // the saved file could not be read back
+    }
+
+    impl fmt::Display for AccountError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                AccountError::InvalidUsername(reason) => write!(f, "invalid username: {}", reason),
+                AccountError::InvalidEmail(reason) => write!(f, "invalid email: {}", reason),
+                AccountError::UsernameTaken(name) => write!(f, "username {} is already taken", name),
+                AccountError::UnknownUser(name) => write!(f, "no user called {}", name),
+                AccountError::Inactive(name) => write!(f, "user {} is deactivated", name),
+                AccountError::Io(e) => write!(f, "I/O error: {}", e),
+                AccountError::Corrupt(reason) => write!(f, "corrupt user file: {}", reason),
+            }
+        }
+    }
+
+    impl std::error::Error for AccountError {}
+
+    impl From<io::Error> for AccountError {
+        fn from(e: io::Error) -> AccountError {
+            AccountError::Io(e)
+        }
+    }
+
+    // 3 to 32 chars of letters, digits, '_', '-' or '.', starting with a letter
+    pub fn validate_username(username: &str) -> Result<(), AccountError> {
+        let invalid = |reason: &str| Err(AccountError::InvalidUsername(format!("{} ({})", reason, username)));
+        let len = username.chars().count();
+        if !(3..=32).contains(&len) {
+            return invalid("must be 3 to 32 characters long");
+        }
+        if !username.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            return invalid("must start with a letter");
+        }
+        if !username.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.')) {
+            return invalid("may only contain letters, digits, '_', '-' and '.'");
+        }
+        Ok(())
+    }
+
+    // deliberately simple: local@domain.tld with no spaces and no empty parts
+    pub fn validate_email(email: &str) -> Result<(), AccountError> {
+        let invalid = |reason: &str| Err(AccountError::InvalidEmail(format!("{} ({})", reason, email)));
+        if email.chars().any(char::is_whitespace) {
+            return invalid("must not contain spaces");
+        }
+        let (local, domain) = match email.split_once('@') {
+            Some(parts) => parts,
+            None => return invalid("missing '@'"),
+        };
+        if local.is_empty() || domain.contains('@') {
+            return invalid("needs exactly one '@' with a name before it");
+        }
+        if !domain.contains('.') || domain.split('.').any(str::is_empty) {
+            return invalid("domain must look like example.com");
+        }
+        Ok(())
+    }
+
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+    }
+
+    // usernames are unique ignoring case, but keep the case they were registered with
+    #[derive(Debug, Default)]
+    pub struct Registry {
+        users: HashMap<String, User>,
+    }
+
+    impl Registry {
+        pub fn new() -> Registry {
+            Registry::default()
+        }
+
+        pub fn get(&self, username: &str) -> Option<&User> {
+            self.users.get(&username.to_lowercase())
+        }
+
+        fn get_mut(&mut self, username: &str) -> Result<&mut User, AccountError> {
+            self.users
+                .get_mut(&username.to_lowercase())
+                .ok_or_else(|| AccountError::UnknownUser(username.to_string()))
+        }
+
+        pub fn users(&self) -> impl Iterator<Item = &User> {
+            self.users.values()
+        }
+
+        // like `build_user`: a new user is active and signed in once
+        pub fn register(&mut self, username: &str, email: &str) -> Result<&User, AccountError> {
+            self.register_at(username, email, now())
+        }
+
+        pub fn register_at(&mut self, username: &str, email: &str, time: u64) -> Result<&User, AccountError> {
+            validate_username(username)?;
+            validate_email(email)?;
+            let key = username.to_lowercase();
+            if self.users.contains_key(&key) {
+                return Err(AccountError::UsernameTaken(username.to_string()));
+            }
+            let user = User {
+                active: true,
+                username: username.to_string(),
+                email: email.to_string(),
+                sign_in_count: 1,
+                created_at: time,
+                sign_ins: vec![time],
+            };
+            Ok(self.users.entry(key).or_insert(user))
+        }
+
+        // returns the new sign-in count
+        pub fn sign_in(&mut self, username: &str) -> Result<u64, AccountError> {
+            self.sign_in_at(username, now())
+        }
+
+        pub fn sign_in_at(&mut self, username: &str, time: u64) -> Result<u64, AccountError> {
+            let user = self.get_mut(username)?;
+            if !user.active {
+                return Err(AccountError::Inactive(user.username.clone()));
+            }
+            user.sign_in_count += 1;
+            user.sign_ins.push(time);
+            Ok(user.sign_in_count)
+        }
+
+        pub fn change_email(&mut self, username: &str, email: &str) -> Result<(), AccountError> {
+            validate_email(email)?;
+            self.get_mut(username)?.email = email.to_string();
+            Ok(())
+        }
+
+        pub fn deactivate(&mut self, username: &str) -> Result<(), AccountError> {
+            self.get_mut(username)?.active = false;
+            Ok(())
+        }
+
+        pub fn reactivate(&mut self, username: &str) -> Result<(), AccountError> {
+            self.get_mut(username)?.active = true;
+            Ok(())
+        }
+
+        pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), AccountError> {
+            let mut users: Vec<&User> = self.users.values().collect();
+            users.sort_by(|a, b| a.username.cmp(&b.username));
+            let mut out = String::from("{\n  \"users\": [");
+            for (i, user) in users.iter().enumerate() {
+                let sign_ins: Vec<String> = user.sign_ins.iter().map(|t| t.to_string()).collect();
+                out.push_str(if i == 0 { "\n" } else { ",\n" });
+                out.push_str(&format!(
+                    "    {{\"username\": {}, \"email\": {}, \"active\": {}, \"sign_in_count\": {}, \"created_at\": {}, \"sign_ins\": [{}]}}",
+                    json::quote(&user.username),
+                    json::quote(&user.email),
+                    user.active,
+                    user.sign_in_count,
+                    user.created_at,
+                    sign_ins.join(", ")
+                ));
+            }
+            out.push_str("\n  ]\n}\n");
+            fs::write(path, out)?;
+            Ok(())
+        }
+
+        // the loaded users are validated again, so a hand-edited file can't
+        // sneak in a bad email or a duplicate username
+        pub fn load<P: AsRef<Path>>(path: P) -> Result<Registry, AccountError> {
+            let text = fs::read_to_string(path)?;
+            let value = json::parse(&text).map_err(AccountError::Corrupt)?;
+            let corrupt = |what: &str| AccountError::Corrupt(format!("missing or invalid {}", what));
+            let list = value.get("users").and_then(json::Value::as_array).ok_or_else(|| corrupt("users"))?;
+
+            let mut registry = Registry::new();
+            for entry in list {
+                let text = |key: &str| entry.get(key).and_then(json::Value::as_str).ok_or_else(|| corrupt(key));
+                let number = |key: &str| entry.get(key).and_then(json::Value::as_u64).ok_or_else(|| corrupt(key));
+                let username = text("username")?;
+                let email = text("email")?;
+                validate_username(username)?;
+                validate_email(email)?;
+                let key = username.to_lowercase();
+                if registry.users.contains_key(&key) {
+                    return Err(AccountError::UsernameTaken(username.to_string()));
+                }
+                let sign_ins = entry
+                    .get("sign_ins")
+                    .and_then(json::Value::as_array)
+                    .ok_or_else(|| corrupt("sign_ins"))?
+                    .iter()
+                    .map(|t| t.as_u64().ok_or_else(|| corrupt("sign_ins")))
+                    .collect::<Result<Vec<u64>, AccountError>>()?;
+                let user = User {
+                    active: entry.get("active").and_then(json::Value::as_bool).ok_or_else(|| corrupt("active"))?,
+                    username: username.to_string(),
+                    email: email.to_string(),
+                    sign_in_count: number("sign_in_count")?,
+                    created_at: number("created_at")?,
+                    sign_ins,
+                };
+                registry.users.insert(key, user);
+            }
+            Ok(registry)
+        }
+    }
+
+    // just enough JSON for the user file: no external crates needed
+    mod json {
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum Value {
+            Null,
+            Bool(bool),
+            Number(f64),
+            Text(String),
+            Array(Vec<Value>),
+            Object(Vec<(String, Value)>),
+        }
+
+        impl Value {
+            pub fn get(&self, key: &str) -> Option<&Value> {
+                match self {
+                    Value::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+                    _ => None,
+                }
+            }
+
+            pub fn as_str(&self) -> Option<&str> {
+                match self {
+                    Value::Text(s) => Some(s),
+                    _ => None,
+                }
+            }
+
+            pub fn as_bool(&self) -> Option<bool> {
+                match self {
+                    Value::Bool(b) => Some(*b),
+                    _ => None,
+                }
+            }
+
+            pub fn as_u64(&self) -> Option<u64> {
+                match self {
+                    Value::Number(n) if *n >= 0.0 && n.fract() == 0.0 => Some(*n as u64),
+                    _ => None,
+                }
+            }
+
+            pub fn as_array(&self) -> Option<&Vec<Value>> {
+                match self {
+                    Value::Array(items) => Some(items),
+                    _ => None,
+                }
+            }
+        }
+
+        pub fn quote(s: &str) -> String {
+            let mut out = String::from("\"");
+            for c in s.chars() {
+                match c {
+                    '"' => out.push_str("\\\""),
+                    '\\' => out.push_str("\\\\"),
+                    '\n' => out.push_str("\\n"),
+                    c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+                    c => out.push(c),
+                }
+            }
+            out.push('"');
+            out
+        }
+
+        pub fn parse(text: &str) -> Result<Value, String> {
+            let mut parser = Parser { chars: text.chars().collect(), pos: 0 };
+            let value = parser.value()?;
+            parser.skip_whitespace();
+            if parser.pos != parser.chars.len() {
+                return Err(format!("unexpected trailing data at {}", parser.pos));
+            }
+            Ok(value)
+        }
+
+        struct Parser {
+            chars: Vec<char>,
+            pos: usize,
+        }
+
+        impl Parser {
+            fn skip_whitespace(&mut self) {
+                while self.chars.get(self.pos).is_some_and(|c| c.is_whitespace()) {
+                    self.pos += 1;
+                }
+            }
+
+            fn expect(&mut self, c: char) -> Result<(), String> {
+                self.skip_whitespace();
+                if self.chars.get(self.pos) == Some(&c) {
+                    self.pos += 1;
+                    Ok(())
+                } else {
+                    Err(format!("expected '{}' at {}", c, self.pos))
+                }
+            }
+
+            fn literal(&mut self, word: &str, value: Value) -> Result<Value, String> {
+                let end = self.pos + word.len();
+                if end <= self.chars.len() && self.chars[self.pos..end].iter().copied().eq(word.chars()) {
+                    self.pos = end;
+                    Ok(value)
+                } else {
+                    Err(format!("unexpected input at {}", self.pos))
+                }
+            }
+
+            fn value(&mut self) -> Result<Value, String> {
+                self.skip_whitespace();
+                match self.chars.get(self.pos) {
+                    Some('{') => self.object(),
+                    Some('[') => self.array(),
+                    Some('"') => self.string().map(Value::Text),
+                    Some('t') => self.literal("true", Value::Bool(true)),
+                    Some('f') => self.literal("false", Value::Bool(false)),
+                    Some('n') => self.literal("null", Value::Null),
+                    Some(_) => self.number(),
+                    None => Err(String::from("unexpected end of input")),
+                }
+            }
+
+            fn object(&mut self) -> Result<Value, String> {
+                self.expect('{')?;
+                let mut fields = Vec::new();
+                self.skip_whitespace();
+                if self.chars.get(self.pos) == Some(&'}') {
+                    self.pos += 1;
+                    return Ok(Value::Object(fields));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let key = self.string()?;
+                    self.expect(':')?;
+                    fields.push((key, self.value()?));
+                    self.skip_whitespace();
+                    match self.chars.get(self.pos) {
+                        Some(',') => self.pos += 1,
+                        Some('}') => {
+                            self.pos += 1;
+                            return Ok(Value::Object(fields));
+                        }
+                        _ => return Err(format!("expected ',' or '}}' at {}", self.pos)),
+                    }
+                }
+            }
+
+            fn array(&mut self) -> Result<Value, String> {
+                self.expect('[')?;
+                let mut items = Vec::new();
+                self.skip_whitespace();
+                if self.chars.get(self.pos) == Some(&']') {
+                    self.pos += 1;
+                    return Ok(Value::Array(items));
+                }
+                loop {
+                    items.push(self.value()?);
+                    self.skip_whitespace();
+                    match self.chars.get(self.pos) {
+                        Some(',') => self.pos += 1,
+                        Some(']') => {
+                            self.pos += 1;
+                            return Ok(Value::Array(items));
+                        }
+                        _ => return Err(format!("expected ',' or ']' at {}", self.pos)),
+                    }
+                }
+            }
+
+            fn string(&mut self) -> Result<String, String> {
+                self.expect('"')?;
+                let mut out = String::new();
+                loop {
+                    let c = *self.chars.get(self.pos).ok_or("unterminated string")?;
+                    self.pos += 1;
+                    match c {
+                        '"' => return Ok(out),
+                        '\\' => {
+                            let escaped = *self.chars.get(self.pos).ok_or("unterminated string")?;
+                            self.pos += 1;
+                            match escaped {
+                                'n' => out.push('\n'),
+                                't' => out.push('\t'),
+                                'r' => out.push('\r'),
+                                'u' => {
+                                    let hex: String = self.chars.iter().skip(self.pos).take(4).collect();
+                                    let code = u32::from_str_radix(&hex, 16).map_err(|_| format!("bad \\u escape at {}", self.pos))?;
+                                    out.push(char::from_u32(code).unwrap_or('\u{FFFD}'));
+                                    self.pos += 4;
+                                }
+                                other => out.push(other), // \" \\ \/
+                            }
+                        }
+                        c => out.push(c),
+                    }
+                }
+            }
+
+            fn number(&mut self) -> Result<Value, String> {
+                let start = self.pos;
+                while self.chars.get(self.pos).is_some_and(|c| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E')) {
+                    self.pos += 1;
+                }
+                let text: String = self.chars[start..self.pos].iter().collect();
+                text.parse().map(Value::Number).map_err(|_| format!("invalid number at {}", start))
+            }
+        }
+    }
+}
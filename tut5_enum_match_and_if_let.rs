@@ -1,4 +1,22 @@
 fn main() {
+    // `play [SEED]` plays the board game at the end against the terminal
+    // instead of running the examples
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("play") {
+        let seed = match args.get(1).map(|seed| seed.parse()) {
+            None => 7,
+            Some(Ok(seed)) => seed,
+            Some(Err(_)) => {
+                eprintln!("usage: play [SEED]");
+                std::process::exit(2);
+            }
+        };
+        if let Err(e) = board_game::play_in_terminal(&["you", "me"], seed) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
 
     //--------------enum--------------
         enum IpAddrKind {
@@ -17,6 +35,32 @@ fn main() {
         kind: IpAddrKind::V6,
         address: String::from("::1"),
     };
+    // nothing stops `kind: IpAddrKind::V4` with `address: "::1"`; putting the
+    // data inside the variants makes that unrepresentable
+    let home: ip::IpAddr = "127.0.0.1".parse().unwrap(); // ip::IpAddr::V4([127, 0, 0, 1])
+    let loopback: ip::IpAddr = "0:0:0:0:0:0:0:1".parse().unwrap();
+    println!("{} {}", loopback, loopback.is_loopback()); // "::1 true"
+    println!("{:?}", "::1".parse::<ip::IpAddr>().map(|a| a.is_private())); // Ok(false)
+    println!("{:?}", "256.1.1.1".parse::<ip::IpAddr>()); // Err(..)
+    println!("{:?}", "::ffff:1.2.3.4".parse::<ip::IpAddr>()); // Ok(V6(..)): IPv4 may end an IPv6 address
+    println!("{:?}", "1.2.3.4::".parse::<ip::IpAddr>()); // Err(..): but not come before the "::"
+    for text in ["::", "ff02::1", "fe80::1", "169.254.0.7"] {
+        let addr: ip::IpAddr = text.parse().unwrap();
+        println!(
+            "{}: v6 {}, unspecified {}, multicast {}, link-local {}",
+            addr,
+            addr.is_ipv6(),
+            addr.is_unspecified(),
+            addr.is_multicast(),
+            addr.is_link_local()
+        );
+    }
+    let subnet: ip::Cidr = "192.168.1.77/30".parse().unwrap();
+    println!("{} contains {}? {}", subnet, home, subnet.contains(&home)); // 192.168.1.76/30 ... false
+    println!("{} /{} holds {}", subnet.network(), subnet.prefix(), subnet.size()); // 192.168.1.76 /30 holds 4
+    for addr in subnet.iter() {
+        println!("{}", addr); // 192.168.1.76 to 192.168.1.79
+    }
     
     //--------------enum with value--------------
     // struct QuitMessage; // unit struct
@@ -39,6 +83,22 @@ fn main() {
     }
     let m = Message::Write(String::from("hello"));
     m.call();
+    
+    //--------------message bus--------------
+    // the same variants, but applied to a target instead of just printed
+    use message_bus::{Bus, Message as BusMessage, MessageKind};
+    let mut bus = Bus::new();
+    bus.on(MessageKind::Write, |message, _| println!("handler saw {:?}", message));
+    bus.send(BusMessage::Move { x: 3, y: 4 });
+    bus.send(BusMessage::Write(String::from("hello")));
+    bus.send(BusMessage::ChangeColor(0, 160, 255));
+    bus.send(BusMessage::Quit);
+    bus.send(BusMessage::Move { x: 1, y: 1 }); // ignored, the target has quit
+    println!("{} queued", bus.pending()); // 5 queued
+    bus.run();
+    println!("{:?}", bus.state()); // position (3, 4), log ["hello"], colour (0, 160, 255), not running
+    let recording = bus.recording(); // "M 3 4\nW hello\nC 0 160 255\nQ\n"
+    println!("{:?}", message_bus::replay(&recording).map(|state| state == *bus.state())); // Ok(true)
 
     //--------------option enum--------------
     let some_number = Some(5);
@@ -102,6 +162,23 @@ fn main() {
     let six = plus_one(five);
     let none = plus_one(None);
     
+    //--------------coin purse and cash register--------------
+    use money::{Coin as UsCoin, Purse, Register, UsState as State};
+    let mut purse = Purse::new();
+    purse.add(UsCoin::Quarter(State::Alaska));
+    purse.add(UsCoin::Quarter(State::Alaska));
+    purse.add(UsCoin::Quarter(State::Alabama));
+    purse.add(UsCoin::Dime);
+    purse.add(UsCoin::Penny);
+    println!("{} cents in {} coins", purse.total_cents(), purse.len()); // 86 cents in 5 coins
+    println!("{:?}", purse.pay(35)); // a dime and the spare Alaska quarter
+    println!("{:?}", money::greedy_change(30, &[25, 10, 1])); // Some([1, 0, 5]): 6 coins
+    println!("{:?}", money::min_coin_change(30, &[25, 10, 1])); // Some([0, 3, 0]): 3 coins
+    println!("{}", purse.collection_report());
+    let mut register = Register::new(Purse::from_coins(vec![UsCoin::Dime; 10]));
+    println!("{:?}", register.checkout(65, vec![UsCoin::Quarter(State::Texas); 3])); // Ok([Dime])
+    println!("{} cents in the drawer, empty: {}", register.drawer().total_cents(), register.drawer().is_empty()); // 165, false
+    
     //--------------matching all cases--------------
     let dice_roll = 9;
     match dice_roll {
@@ -118,6 +195,23 @@ fn main() {
     fn remove_fancy_hat() {}
     fn move_player(num_spaces: u8) {}
     
+    //--------------board game engine--------------
+    // the same roll rules driving a real game: 3 adds a hat, 7 removes one,
+    // anything else moves the player
+    use board_game::{Action, Board, Game, SeededDice};
+    let mut game = Game::new(Board::classic(), &["Ferris", "Corro"], SeededDice::new(42));
+    game.on_roll(12, Action::MovePlayer(24)); // house rule: double six moves twice as far
+    while game.winner().is_none() {
+        game.take_turn();
+    }
+    for line in game.log() {
+        println!("{}", line);
+    }
+    for player in game.players() {
+        println!("{}: square {}, {} hat(s)", player.name, player.position, player.hats);
+    }
+    // run with `play [SEED]` to press Enter before each roll yourself
+    
     //--------------if let--------------
     //example 1
     let mut count = 0;
@@ -145,3 +239,1229 @@ fn main() {
 }
 
 
+
+mod ip {
+    use std::fmt;
+    use std::str::FromStr;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub enum IpAddr {
+        V4([u8; 4]),
+        V6([u16; 8]),
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct AddrParseError(String);
+
+    impl fmt::Display for AddrParseError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "invalid IP address: {}", self.0)
+        }
+    }
+
+    impl std::error::Error for AddrParseError {}
+
+    fn error(reason: &str, text: &str) -> AddrParseError {
+        AddrParseError(format!("{} ({:?})", reason, text))
+    }
+
+    fn parse_v4(text: &str) -> Result<[u8; 4], AddrParseError> {
+        let mut octets = [0u8; 4];
+        let mut parts = text.split('.');
+        for octet in octets.iter_mut() {
+            let part = parts.next().ok_or_else(|| error("IPv4 needs four parts", text))?;
+            // "01" is rejected because some tools read it as octal
+            if part.is_empty() || part.len() > 3 || !part.chars().all(|c| c.is_ascii_digit()) || (part.len() > 1 && part.starts_with('0')) {
+                return Err(error("bad IPv4 part", text));
+            }
+            *octet = part.parse().map_err(|_| error("IPv4 parts must be 0-255", text))?;
+        }
+        if parts.next().is_some() {
+            return Err(error("IPv4 needs four parts", text));
+        }
+        Ok(octets)
+    }
+
+    // `ends_address` is false for the groups before a "::"
+    fn parse_groups(text: &str, full: &str, ends_address: bool) -> Result<Vec<u16>, AddrParseError> {
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        let mut groups = Vec::new();
+        let parts: Vec<&str> = text.split(':').collect();
+        for (i, part) in parts.iter().enumerate() {
+            // the address's last part may be an embedded IPv4 address, as in
+            // ::ffff:1.2.3.4, but nowhere else ("1.2.3.4::" is not an address)
+            if ends_address && i == parts.len() - 1 && part.contains('.') {
+                let [a, b, c, d] = parse_v4(part)?;
+                groups.push(u16::from_be_bytes([a, b]));
+                groups.push(u16::from_be_bytes([c, d]));
+            } else if part.is_empty() || part.len() > 4 {
+                return Err(error("IPv6 groups are 1 to 4 hex digits", full));
+            } else {
+                groups.push(u16::from_str_radix(part, 16).map_err(|_| error("bad IPv6 group", full))?);
+            }
+        }
+        Ok(groups)
+    }
+
+    fn parse_v6(text: &str) -> Result<[u16; 8], AddrParseError> {
+        let mut segments = [0u16; 8];
+        match text.split_once("::") {
+            Some((head, tail)) => {
+                if tail.contains("::") {
+                    return Err(error("'::' may only appear once", text));
+                }
+                let head = parse_groups(head, text, false)?;
+                let tail = parse_groups(tail, text, true)?;
+                if head.len() + tail.len() > 7 {
+                    return Err(error("too many IPv6 groups", text));
+                }
+                segments[..head.len()].copy_from_slice(&head);
+                segments[8 - tail.len()..].copy_from_slice(&tail);
+            }
+            None => {
+                let groups = parse_groups(text, text, true)?;
+                if groups.len() != 8 {
+                    return Err(error("IPv6 needs eight groups or '::'", text));
+                }
+                segments.copy_from_slice(&groups);
+            }
+        }
+        Ok(segments)
+    }
+
+    impl FromStr for IpAddr {
+        type Err = AddrParseError;
+
+        fn from_str(text: &str) -> Result<IpAddr, AddrParseError> {
+            if text.contains(':') {
+                parse_v6(text).map(IpAddr::V6)
+            } else {
+                parse_v4(text).map(IpAddr::V4)
+            }
+        }
+    }
+
+    // canonical form (RFC 5952 for IPv6): lowercase, no leading zeros, and the
+    // longest run of two or more zero groups written as "::"
+    impl fmt::Display for IpAddr {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let segments = match self {
+                IpAddr::V4([a, b, c, d]) => return write!(f, "{}.{}.{}.{}", a, b, c, d),
+                IpAddr::V6(segments) => segments,
+            };
+            if let Some([a, b, c, d]) = self.to_ipv4_mapped() {
+                return write!(f, "::ffff:{}.{}.{}.{}", a, b, c, d);
+            }
+            let (mut best_start, mut best_len) = (0, 0);
+            let mut i = 0;
+            while i < 8 {
+                let run = segments[i..].iter().take_while(|&&s| s == 0).count();
+                if run > best_len {
+                    best_start = i;
+                    best_len = run;
+                }
+                i += run.max(1);
+            }
+            let join = |groups: &[u16]| groups.iter().map(|g| format!("{:x}", g)).collect::<Vec<_>>().join(":");
+            if best_len < 2 {
+                write!(f, "{}", join(segments))
+            } else {
+                let (head, rest) = segments.split_at(best_start);
+                write!(f, "{}::{}", join(head), join(&rest[best_len..]))
+            }
+        }
+    }
+
+    impl IpAddr {
+        pub fn is_ipv4(&self) -> bool {
+            matches!(self, IpAddr::V4(_))
+        }
+
+        pub fn is_ipv6(&self) -> bool {
+            matches!(self, IpAddr::V6(_))
+        }
+
+        // the address as a number, handy for masks and ranges
+        pub fn to_bits(self) -> u128 {
+            match self {
+                IpAddr::V4(octets) => u32::from_be_bytes(octets) as u128,
+                IpAddr::V6(segments) => segments.iter().fold(0, |bits, &s| (bits << 16) | s as u128),
+            }
+        }
+
+        fn from_bits(bits: u128, v4: bool) -> IpAddr {
+            if v4 {
+                IpAddr::V4((bits as u32).to_be_bytes())
+            } else {
+                let mut segments = [0u16; 8];
+                for (i, segment) in segments.iter_mut().enumerate() {
+                    *segment = (bits >> (112 - 16 * i)) as u16;
+                }
+                IpAddr::V6(segments)
+            }
+        }
+
+        // ::ffff:a.b.c.d
+        pub fn to_ipv4_mapped(self) -> Option<[u8; 4]> {
+            match self {
+                IpAddr::V6([0, 0, 0, 0, 0, 0xffff, hi, lo]) => {
+                    let [a, b] = hi.to_be_bytes();
+                    let [c, d] = lo.to_be_bytes();
+                    Some([a, b, c, d])
+                }
+                _ => None,
+            }
+        }
+
+        pub fn is_unspecified(&self) -> bool {
+            self.to_bits() == 0
+        }
+
+        pub fn is_loopback(&self) -> bool {
+            match self {
+                IpAddr::V4([a, ..]) => *a == 127,
+                IpAddr::V6(_) => self.to_bits() == 1,
+            }
+        }
+
+        // RFC 1918 for IPv4, unique local fc00::/7 for IPv6
+        pub fn is_private(&self) -> bool {
+            match self {
+                IpAddr::V4([10, ..]) => true,
+                IpAddr::V4([172, b, ..]) => (16..=31).contains(b),
+                IpAddr::V4([192, 168, ..]) => true,
+                IpAddr::V4(_) => false,
+                IpAddr::V6([first, ..]) => first & 0xfe00 == 0xfc00,
+            }
+        }
+
+        pub fn is_multicast(&self) -> bool {
+            match self {
+                IpAddr::V4([a, ..]) => (224..=239).contains(a),
+                IpAddr::V6([first, ..]) => first & 0xff00 == 0xff00,
+            }
+        }
+
+        pub fn is_link_local(&self) -> bool {
+            match self {
+                IpAddr::V4([169, 254, ..]) => true,
+                IpAddr::V4(_) => false,
+                IpAddr::V6([first, ..]) => first & 0xffc0 == 0xfe80,
+            }
+        }
+    }
+
+    // a subnet such as 10.0.0.0/8; the host bits are cleared on parse, so
+    // "192.168.1.77/24" becomes 192.168.1.0/24
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Cidr {
+        network: IpAddr,
+        prefix: u8,
+    }
+
+    impl Cidr {
+        pub fn new(addr: IpAddr, prefix: u8) -> Result<Cidr, AddrParseError> {
+            let width = if addr.is_ipv4() { 32 } else { 128 };
+            if prefix > width {
+                return Err(AddrParseError(format!("prefix /{} is longer than {} bits", prefix, width)));
+            }
+            let network = IpAddr::from_bits(addr.to_bits() & Cidr::mask(prefix, width), addr.is_ipv4());
+            Ok(Cidr { network, prefix })
+        }
+
+        fn mask(prefix: u8, width: u8) -> u128 {
+            let all = if width == 32 { u32::MAX as u128 } else { u128::MAX };
+            if prefix == 0 {
+                0
+            } else {
+                all & (all << (width - prefix))
+            }
+        }
+
+        fn width(&self) -> u8 {
+            if self.network.is_ipv4() { 32 } else { 128 }
+        }
+
+        pub fn network(&self) -> IpAddr {
+            self.network
+        }
+
+        pub fn prefix(&self) -> u8 {
+            self.prefix
+        }
+
+        // number of addresses; saturates for ::/0, which has 2^128
+        pub fn size(&self) -> u128 {
+            1u128.checked_shl((self.width() - self.prefix) as u32).unwrap_or(u128::MAX)
+        }
+
+        pub fn contains(&self, addr: &IpAddr) -> bool {
+            addr.is_ipv4() == self.network.is_ipv4()
+                && addr.to_bits() & Cidr::mask(self.prefix, self.width()) == self.network.to_bits()
+        }
+
+        // every address in the subnet, network and broadcast included
+        pub fn iter(&self) -> impl Iterator<Item = IpAddr> {
+            let first = self.network.to_bits();
+            let last = first | !Cidr::mask(self.prefix, self.width()) & Cidr::mask(self.width(), self.width());
+            let v4 = self.network.is_ipv4();
+            (first..=last).map(move |bits| IpAddr::from_bits(bits, v4))
+        }
+    }
+
+    impl FromStr for Cidr {
+        type Err = AddrParseError;
+
+        fn from_str(text: &str) -> Result<Cidr, AddrParseError> {
+            let (addr, prefix) = text.split_once('/').ok_or_else(|| error("CIDR needs a /prefix", text))?;
+            let prefix = prefix.parse().map_err(|_| error("bad prefix length", text))?;
+            Cidr::new(addr.parse()?, prefix)
+        }
+    }
+
+    impl fmt::Display for Cidr {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}/{}", self.network, self.prefix)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn addr(text: &str) -> IpAddr {
+            text.parse().unwrap()
+        }
+
+        fn cidr(text: &str) -> Cidr {
+            text.parse().unwrap()
+        }
+
+        #[test]
+        fn leading_zeros() {
+            assert_eq!(addr("0.0.0.0"), IpAddr::V4([0, 0, 0, 0]));
+            assert_eq!(addr("10.0.100.255"), IpAddr::V4([10, 0, 100, 255]));
+            for text in ["01.2.3.4", "1.2.3.004", "00.0.0.0", "256.0.0.1", "1.2.3", "1.2.3.4.5", "1..3.4"] {
+                assert!(text.parse::<IpAddr>().is_err(), "{}", text);
+            }
+            // IPv6 groups may be padded up to four digits, and print without it
+            assert_eq!(addr("0001:0db8::00ff"), IpAddr::V6([1, 0xdb8, 0, 0, 0, 0, 0, 0xff]));
+            assert_eq!(addr("0001:0db8::00ff").to_string(), "1:db8::ff");
+            assert!("00001::".parse::<IpAddr>().is_err());
+        }
+
+        #[test]
+        fn compressed_ipv6() {
+            assert_eq!(addr("::"), IpAddr::V6([0; 8]));
+            assert_eq!(addr("::1"), IpAddr::V6([0, 0, 0, 0, 0, 0, 0, 1]));
+            assert_eq!(addr("1::"), IpAddr::V6([1, 0, 0, 0, 0, 0, 0, 0]));
+            assert_eq!(addr("1:2::7:8"), IpAddr::V6([1, 2, 0, 0, 0, 0, 7, 8]));
+            assert_eq!(addr("1:2:3:4:5:6:7::"), IpAddr::V6([1, 2, 3, 4, 5, 6, 7, 0]));
+            assert_eq!(addr("::ffff:1.2.3.4"), IpAddr::V6([0, 0, 0, 0, 0, 0xffff, 0x102, 0x304]));
+            for text in ["1::2::3", "1:2:3:4:5:6:7::8", ":1::", "1::2:", "1.2.3.4::", "1:2:3:4:5:6:7"] {
+                assert!(text.parse::<IpAddr>().is_err(), "{}", text);
+            }
+
+            // printing compresses the longest run of zeros, the first one on a tie
+            assert_eq!(addr("1:0:0:1:0:0:0:1").to_string(), "1:0:0:1::1");
+            assert_eq!(addr("1:0:0:1:0:0:1:1").to_string(), "1::1:0:0:1:1");
+            assert_eq!(addr("0:0:1:0:0:0:0:0").to_string(), "0:0:1::");
+            assert_eq!(addr("1:0:1:0:1:0:1:0").to_string(), "1:0:1:0:1:0:1:0");
+        }
+
+        #[test]
+        fn prefix_lengths() {
+            assert_eq!(cidr("10.1.2.3/32").size(), 1);
+            assert_eq!(cidr("10.1.2.3/0").network(), addr("0.0.0.0"));
+            assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+            assert_eq!(cidr("::1/128").size(), 1);
+            assert_eq!(cidr("::/0").size(), u128::MAX);
+            assert!("::/129".parse::<Cidr>().is_err());
+            assert!("::/300".parse::<Cidr>().is_err());
+            assert!("10.0.0.0".parse::<Cidr>().is_err());
+        }
+
+        #[test]
+        fn contains_at_the_network_boundary() {
+            let net = cidr("192.168.1.77/24");
+            assert_eq!(net.to_string(), "192.168.1.0/24");
+            assert!(net.contains(&addr("192.168.1.0")));
+            assert!(net.contains(&addr("192.168.1.255")));
+            assert!(!net.contains(&addr("192.168.0.255")));
+            assert!(!net.contains(&addr("192.168.2.0")));
+            assert!(!net.contains(&addr("::ffff:192.168.1.1"))); // a different family
+
+            let net = cidr("2001:db8::/32");
+            assert!(net.contains(&addr("2001:db8::")));
+            assert!(net.contains(&addr("2001:db8:ffff:ffff:ffff:ffff:ffff:ffff")));
+            assert!(!net.contains(&addr("2001:db7:ffff:ffff:ffff:ffff:ffff:ffff")));
+            assert!(!net.contains(&addr("2001:db9::")));
+
+            assert!(cidr("0.0.0.0/0").contains(&addr("255.255.255.255")));
+            assert!(cidr("10.0.0.1/32").contains(&addr("10.0.0.1")));
+            assert!(!cidr("10.0.0.1/32").contains(&addr("10.0.0.2")));
+        }
+    }
+}
+
+mod money {
+    use std::collections::BTreeMap;
+    use std::fmt;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub enum UsState {
+        Alabama, Alaska, Arizona, Arkansas, California, Colorado,
+        Connecticut, Delaware, Florida, Georgia, Hawaii, Idaho,
+        Illinois, Indiana, Iowa, Kansas, Kentucky, Louisiana,
+        Maine, Maryland, Massachusetts, Michigan, Minnesota, Mississippi,
+        Missouri, Montana, Nebraska, Nevada, NewHampshire, NewJersey,
+        NewMexico, NewYork, NorthCarolina, NorthDakota, Ohio, Oklahoma,
+        Oregon, Pennsylvania, RhodeIsland, SouthCarolina, SouthDakota, Tennessee,
+        Texas, Utah, Vermont, Virginia, Washington, WestVirginia,
+        Wisconsin, Wyoming,
+    }
+
+    impl UsState {
+        pub const ALL: [UsState; 50] = [
+            UsState::Alabama, UsState::Alaska, UsState::Arizona, UsState::Arkansas,
+            UsState::California, UsState::Colorado, UsState::Connecticut, UsState::Delaware,
+            UsState::Florida, UsState::Georgia, UsState::Hawaii, UsState::Idaho,
+            UsState::Illinois, UsState::Indiana, UsState::Iowa, UsState::Kansas,
+            UsState::Kentucky, UsState::Louisiana, UsState::Maine, UsState::Maryland,
+            UsState::Massachusetts, UsState::Michigan, UsState::Minnesota, UsState::Mississippi,
+            UsState::Missouri, UsState::Montana, UsState::Nebraska, UsState::Nevada,
+            UsState::NewHampshire, UsState::NewJersey, UsState::NewMexico, UsState::NewYork,
+            UsState::NorthCarolina, UsState::NorthDakota, UsState::Ohio, UsState::Oklahoma,
+            UsState::Oregon, UsState::Pennsylvania, UsState::RhodeIsland, UsState::SouthCarolina,
+            UsState::SouthDakota, UsState::Tennessee, UsState::Texas, UsState::Utah,
+            UsState::Vermont, UsState::Virginia, UsState::Washington, UsState::WestVirginia,
+            UsState::Wisconsin, UsState::Wyoming,
+        ];
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum Coin {
+        Penny,
+        Nickel,
+        Dime,
+        Quarter(UsState),
+    }
+
+    impl Coin {
+        pub fn value_in_cents(&self) -> u32 {
+            match self {
+                Coin::Penny => 1,
+                Coin::Nickel => 5,
+                Coin::Dime => 10,
+                Coin::Quarter(_) => 25,
+            }
+        }
+    }
+
+    // the coin values the purse works with, largest first
+    const DENOMINATIONS: [u32; 4] = [25, 10, 5, 1];
+
+    #[derive(Debug, PartialEq)]
+    pub enum MoneyError {
+        CannotMakeChange(u32), // the coins on hand can't add up to this amount
+        NotEnoughPaid { price: u32, paid: u32 },
+    }
+
+    impl fmt::Display for MoneyError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                MoneyError::CannotMakeChange(amount) => write!(f, "can't make exactly {} cents", amount),
+                MoneyError::NotEnoughPaid { price, paid } => write!(f, "paid {} cents for a price of {}", paid, price),
+            }
+        }
+    }
+
+    impl std::error::Error for MoneyError {}
+
+    // fewest coins by always taking the largest coin that fits; optimal for
+    // US coins but not for every set (try 30 with [25, 10, 1])
+    // returns how many of each denomination, in the order given
+    pub fn greedy_change(amount: u32, denominations: &[u32]) -> Option<Vec<u32>> {
+        let mut order: Vec<usize> = (0..denominations.len()).collect();
+        order.sort_by_key(|&i| std::cmp::Reverse(denominations[i]));
+        let mut counts = vec![0; denominations.len()];
+        let mut left = amount;
+        for i in order {
+            if let Some(n) = left.checked_div(denominations[i]) {
+                counts[i] = n;
+                left -= n * denominations[i];
+            }
+        }
+        if left == 0 { Some(counts) } else { None }
+    }
+
+    // fewest coins for any set of denominations, by dynamic programming over
+    // every amount up to `amount`. Past largest² the rest is paid in the
+    // largest coin: among `largest` smaller coins some always add up to a
+    // multiple of it, and swapping those for large coins never costs more, so
+    // the smaller coins never need to cover more than that
+    pub fn min_coin_change(amount: u32, denominations: &[u32]) -> Option<Vec<u32>> {
+        let (top, &largest) = denominations.iter().enumerate().max_by_key(|&(_, &d)| d).unwrap_or((0, &0));
+        if largest == 0 {
+            return (amount == 0).then(|| vec![0; denominations.len()]);
+        }
+        let limit = largest as u64 * largest as u64;
+        let extra = (amount as u64).saturating_sub(limit).div_ceil(largest as u64);
+        let amount = (amount as u64 - extra * largest as u64) as usize;
+        // best[a] = (coins needed for a, index of the last coin used)
+        let mut best: Vec<Option<(u32, usize)>> = vec![None; amount + 1];
+        best[0] = Some((0, usize::MAX));
+        for a in 1..=amount {
+            for (i, &d) in denominations.iter().enumerate() {
+                let d = d as usize;
+                if d == 0 || d > a {
+                    continue;
+                }
+                if let Some((coins, _)) = best[a - d] {
+                    if best[a].is_none_or(|(b, _)| coins + 1 < b) {
+                        best[a] = Some((coins + 1, i));
+                    }
+                }
+            }
+        }
+        best[amount]?;
+        let mut counts = vec![0; denominations.len()];
+        let mut a = amount;
+        while a > 0 {
+            let (_, i) = best[a].unwrap();
+            counts[i] += 1;
+            a -= denominations[i] as usize;
+        }
+        counts[top] += extra as u32;
+        Some(counts)
+    }
+
+    #[derive(Debug, Default, Clone)]
+    pub struct Purse {
+        pennies: u32,
+        nickels: u32,
+        dimes: u32,
+        quarters: BTreeMap<UsState, u32>, // by state, for the collection
+    }
+
+    impl Purse {
+        pub fn new() -> Purse {
+            Purse::default()
+        }
+
+        pub fn from_coins(coins: Vec<Coin>) -> Purse {
+            let mut purse = Purse::new();
+            for coin in coins {
+                purse.add(coin);
+            }
+            purse
+        }
+
+        pub fn add(&mut self, coin: Coin) {
+            match coin {
+                Coin::Penny => self.pennies += 1,
+                Coin::Nickel => self.nickels += 1,
+                Coin::Dime => self.dimes += 1,
+                Coin::Quarter(state) => *self.quarters.entry(state).or_insert(0) += 1,
+            }
+        }
+
+        pub fn quarters(&self) -> u32 {
+            self.quarters.values().sum()
+        }
+
+        // how many coins of each value: [quarters, dimes, nickels, pennies]
+        pub fn counts(&self) -> [u32; 4] {
+            [self.quarters(), self.dimes, self.nickels, self.pennies]
+        }
+
+        pub fn len(&self) -> u32 {
+            self.counts().iter().sum()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len() == 0
+        }
+
+        pub fn total_cents(&self) -> u32 {
+            self.counts().iter().zip(DENOMINATIONS).map(|(n, d)| n * d).sum()
+        }
+
+        // takes out coins adding up to exactly `amount`, using as few coins as
+        // the purse allows; spare state quarters go first so the collection
+        // keeps one of each state for as long as possible
+        pub fn pay(&mut self, amount: u32) -> Result<Vec<Coin>, MoneyError> {
+            // no table for amounts the purse can't reach anyway
+            if amount > self.total_cents() {
+                return Err(MoneyError::CannotMakeChange(amount));
+            }
+            let counts = self.fewest_coins(amount).ok_or(MoneyError::CannotMakeChange(amount))?;
+            let mut coins = Vec::new();
+            for _ in 0..counts[0] {
+                let state = self
+                    .quarters
+                    .iter()
+                    .max_by_key(|&(_, &n)| n)
+                    .map(|(&state, _)| state)
+                    .unwrap();
+                let n = self.quarters.get_mut(&state).unwrap();
+                *n -= 1;
+                if *n == 0 {
+                    self.quarters.remove(&state);
+                }
+                coins.push(Coin::Quarter(state));
+            }
+            self.dimes -= counts[1];
+            self.nickels -= counts[2];
+            self.pennies -= counts[3];
+            coins.extend(std::iter::repeat_n(Coin::Dime, counts[1] as usize));
+            coins.extend(std::iter::repeat_n(Coin::Nickel, counts[2] as usize));
+            coins.extend(std::iter::repeat_n(Coin::Penny, counts[3] as usize));
+            Ok(coins)
+        }
+
+        // like `min_coin_change`, but each coin can only be used as many times
+        // as it is in the purse
+        fn fewest_coins(&self, amount: u32) -> Option<[u32; 4]> {
+            let amount = amount as usize;
+            let mut best: Vec<Option<[u32; 4]>> = vec![None; amount + 1];
+            best[0] = Some([0; 4]);
+            let coins = |counts: &[u32; 4]| counts.iter().sum::<u32>();
+            for (i, (&have, d)) in self.counts().iter().zip(DENOMINATIONS).enumerate() {
+                // one coin at a time, from high amounts down so each is used once
+                for _ in 0..have {
+                    for a in (d as usize..=amount).rev() {
+                        if let Some(mut counts) = best[a - d as usize] {
+                            counts[i] += 1;
+                            if best[a].is_none_or(|b| coins(&counts) < coins(&b)) {
+                                best[a] = Some(counts);
+                            }
+                        }
+                    }
+                }
+            }
+            best[amount]
+        }
+
+        pub fn collection_report(&self) -> String {
+            let missing: Vec<String> = UsState::ALL
+                .iter()
+                .filter(|state| !self.quarters.contains_key(state))
+                .map(|state| format!("{:?}", state))
+                .collect();
+            let spares: u32 = self.quarters.values().map(|n| n - 1).sum();
+            let mut report = format!(
+                "State quarters: {}/50 collected, {} spare",
+                self.quarters.len(),
+                spares
+            );
+            if missing.is_empty() {
+                report.push_str("\nThe collection is complete!");
+            } else {
+                report.push_str(&format!("\nMissing: {}", missing.join(", ")));
+            }
+            report
+        }
+    }
+
+    pub struct Register {
+        drawer: Purse,
+    }
+
+    impl Register {
+        pub fn new(drawer: Purse) -> Register {
+            Register { drawer }
+        }
+
+        pub fn drawer(&self) -> &Purse {
+            &self.drawer
+        }
+
+        // takes the customer's coins and hands back exact change; if the
+        // drawer can't make the change the sale is undone
+        pub fn checkout(&mut self, price: u32, tendered: Vec<Coin>) -> Result<Vec<Coin>, MoneyError> {
+            let paid: u32 = tendered.iter().map(Coin::value_in_cents).sum();
+            if paid < price {
+                return Err(MoneyError::NotEnoughPaid { price, paid });
+            }
+            let before = self.drawer.clone();
+            for coin in tendered {
+                self.drawer.add(coin);
+            }
+            self.drawer.pay(paid - price).inspect_err(|_| self.drawer = before)
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        // 86 cents: two Alaska quarters, one Alabama, a dime and a penny
+        fn purse() -> Purse {
+            Purse::from_coins(vec![
+                Coin::Quarter(UsState::Alaska),
+                Coin::Quarter(UsState::Alaska),
+                Coin::Quarter(UsState::Alabama),
+                Coin::Dime,
+                Coin::Penny,
+            ])
+        }
+
+        #[test]
+        fn exact_payment() {
+            let mut purse = purse();
+            assert_eq!(purse.pay(35), Ok(vec![Coin::Quarter(UsState::Alaska), Coin::Dime]));
+            assert_eq!(purse.total_cents(), 51);
+            assert_eq!(purse.pay(51).map(|coins| coins.len()), Ok(3));
+            assert!(purse.is_empty());
+        }
+
+        #[test]
+        fn impossible_payment() {
+            let mut purse = purse();
+            assert_eq!(purse.pay(5), Err(MoneyError::CannotMakeChange(5)));
+            assert_eq!(purse.total_cents(), 86); // nothing was taken out
+        }
+
+        #[test]
+        fn payment_far_above_the_total() {
+            let mut purse = purse();
+            assert_eq!(purse.pay(u32::MAX), Err(MoneyError::CannotMakeChange(u32::MAX)));
+            assert_eq!(purse.pay(87), Err(MoneyError::CannotMakeChange(87)));
+            assert_eq!(purse.total_cents(), 86);
+        }
+
+        #[test]
+        fn change_for_large_amounts() {
+            assert_eq!(min_coin_change(30, &[25, 10, 1]), Some(vec![0, 3, 0]));
+            assert_eq!(min_coin_change(u32::MAX, &[25, 10, 1]), Some(vec![171_798_691, 2, 0]));
+            assert_eq!(min_coin_change(u32::MAX, &[4, 6]), None); // odd amounts can't be made
+            assert_eq!(min_coin_change(1_000_000, &[4, 6]).map(|c| c[0] * 4 + c[1] * 6), Some(1_000_000));
+            assert_eq!(min_coin_change(3, &[]), None);
+            assert_eq!(min_coin_change(0, &[0]), Some(vec![0]));
+        }
+    }
+}
+
+mod message_bus {
+    use std::collections::{HashMap, VecDeque};
+    use std::fmt;
+    use std::str::FromStr;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Message {
+        Quit,
+        Move { x: i32, y: i32 },
+        Write(String),
+        ChangeColor(i32, i32, i32),
+    }
+
+    // which variant a message is, for registering handlers
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum MessageKind {
+        Quit,
+        Move,
+        Write,
+        ChangeColor,
+    }
+
+    impl Message {
+        pub fn kind(&self) -> MessageKind {
+            match self {
+                Message::Quit => MessageKind::Quit,
+                Message::Move { .. } => MessageKind::Move,
+                Message::Write(_) => MessageKind::Write,
+                Message::ChangeColor(..) => MessageKind::ChangeColor,
+            }
+        }
+
+        // what `call` should have done
+        pub fn apply(&self, state: &mut State) {
+            match self {
+                Message::Quit => state.running = false,
+                Message::Move { x, y } => state.position = (*x, *y),
+                Message::Write(text) => state.log.push(text.clone()),
+                Message::ChangeColor(r, g, b) => state.color = (*r, *g, *b),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct State {
+        pub position: (i32, i32),
+        pub log: Vec<String>,
+        pub color: (i32, i32, i32),
+        pub running: bool,
+    }
+
+    impl Default for State {
+        fn default() -> State {
+            State { position: (0, 0), log: Vec::new(), color: (0, 0, 0), running: true }
+        }
+    }
+
+    // one message per line:
+    //   Q | M <x> <y> | W <text> | C <r> <g> <b>
+    // in W lines a backslash is written as \\ and a newline as \n
+    impl fmt::Display for Message {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Message::Quit => write!(f, "Q"),
+                Message::Move { x, y } => write!(f, "M {} {}", x, y),
+                Message::Write(text) => write!(f, "W {}", text.replace('\\', "\\\\").replace('\n', "\\n")),
+                Message::ChangeColor(r, g, b) => write!(f, "C {} {} {}", r, g, b),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct ParseMessageError(String);
+
+    impl fmt::Display for ParseMessageError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "invalid message: {}", self.0)
+        }
+    }
+
+    impl std::error::Error for ParseMessageError {}
+
+    impl FromStr for Message {
+        type Err = ParseMessageError;
+
+        fn from_str(line: &str) -> Result<Message, ParseMessageError> {
+            let error = || ParseMessageError(String::from(line));
+            let (tag, rest) = line.split_once(' ').unwrap_or((line, ""));
+            if tag == "W" {
+                let mut text = String::new();
+                let mut chars = rest.chars();
+                while let Some(c) = chars.next() {
+                    if c != '\\' {
+                        text.push(c);
+                        continue;
+                    }
+                    match chars.next() {
+                        Some('n') => text.push('\n'),
+                        Some('\\') => text.push('\\'),
+                        _ => return Err(error()),
+                    }
+                }
+                return Ok(Message::Write(text));
+            }
+            let numbers = rest
+                .split_whitespace()
+                .map(|n| n.parse::<i32>().map_err(|_| error()))
+                .collect::<Result<Vec<i32>, ParseMessageError>>()?;
+            match (tag, numbers.as_slice()) {
+                ("Q", []) => Ok(Message::Quit),
+                ("M", &[x, y]) => Ok(Message::Move { x, y }),
+                ("C", &[r, g, b]) => Ok(Message::ChangeColor(r, g, b)),
+                _ => Err(error()),
+            }
+        }
+    }
+
+    type Handler = Box<dyn FnMut(&Message, &State)>;
+
+    // messages are queued by `send` and applied in order by `run`; after a
+    // Quit the rest of the queue is dropped
+    #[derive(Default)]
+    pub struct Bus {
+        queue: VecDeque<Message>,
+        handlers: HashMap<MessageKind, Vec<Handler>>,
+        state: State,
+        applied: Vec<Message>, // for `recording`
+    }
+
+    impl Bus {
+        pub fn new() -> Bus {
+            Bus::default()
+        }
+
+        // handlers run after the message has been applied
+        pub fn on<F>(&mut self, kind: MessageKind, handler: F)
+        where
+            F: FnMut(&Message, &State) + 'static,
+        {
+            self.handlers.entry(kind).or_default().push(Box::new(handler));
+        }
+
+        pub fn send(&mut self, message: Message) {
+            self.queue.push_back(message);
+        }
+
+        pub fn pending(&self) -> usize {
+            self.queue.len()
+        }
+
+        pub fn state(&self) -> &State {
+            &self.state
+        }
+
+        // applies the next queued message; false once there is nothing left to do
+        pub fn step(&mut self) -> bool {
+            if !self.state.running {
+                self.queue.clear();
+                return false;
+            }
+            let message = match self.queue.pop_front() {
+                Some(message) => message,
+                None => return false,
+            };
+            message.apply(&mut self.state);
+            if let Some(handlers) = self.handlers.get_mut(&message.kind()) {
+                for handler in handlers {
+                    handler(&message, &self.state);
+                }
+            }
+            self.applied.push(message);
+            true
+        }
+
+        pub fn run(&mut self) {
+            while self.step() {}
+        }
+
+        // every applied message in the text protocol, ready for `replay`
+        pub fn recording(&self) -> String {
+            self.applied.iter().map(|m| format!("{}\n", m)).collect()
+        }
+    }
+
+    // rebuilds the state from a recording; blank lines are skipped
+    pub fn replay(recording: &str) -> Result<State, ParseMessageError> {
+        let mut bus = Bus::new();
+        for line in recording.lines().filter(|line| !line.trim().is_empty()) {
+            bus.send(line.parse()?);
+        }
+        bus.run();
+        Ok(bus.state)
+    }
+}
+
+mod board_game {
+    use std::collections::HashMap;
+    use std::io::{self, BufRead};
+
+    // two six-sided dice, so rolls are 2..=12
+    pub trait Dice {
+        fn roll(&mut self) -> u8;
+    }
+
+    // xorshift64*: tiny, seedable and plenty random for a board game
+    pub struct SeededDice {
+        state: u64,
+    }
+
+    impl SeededDice {
+        pub fn new(seed: u64) -> SeededDice {
+            SeededDice { state: seed.max(1) }
+        }
+
+        fn next(&mut self) -> u64 {
+            self.state ^= self.state >> 12;
+            self.state ^= self.state << 25;
+            self.state ^= self.state >> 27;
+            self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+    }
+
+    impl Dice for SeededDice {
+        fn roll(&mut self) -> u8 {
+            let die = |n: u64| (n % 6) as u8 + 1;
+            let n = self.next();
+            die(n) + die(n >> 32)
+        }
+    }
+
+    // rolls the given numbers in a loop, for scripting exact games
+    #[cfg(test)]
+    pub struct FixedDice {
+        rolls: Vec<u8>,
+        next: usize,
+    }
+
+    #[cfg(test)]
+    impl FixedDice {
+        pub fn new(rolls: Vec<u8>) -> FixedDice {
+            FixedDice { rolls, next: 0 }
+        }
+    }
+
+    #[cfg(test)]
+    impl Dice for FixedDice {
+        fn roll(&mut self) -> u8 {
+            let roll = self.rolls[self.next % self.rolls.len()];
+            self.next += 1;
+            roll
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Square {
+        Plain,
+        Ladder(usize), // climb up to this square
+        Snake(usize),  // slide down to this square
+        HatShop,       // free fancy hat
+        Thief,         // steals a fancy hat
+        Mud,           // miss the next turn
+    }
+
+    pub struct Board {
+        squares: Vec<Square>,
+    }
+
+    impl Board {
+        // the last square is the finish
+        pub fn new(squares: Vec<Square>) -> Board {
+            assert!(squares.len() >= 2, "a board needs a start and a finish");
+            Board { squares }
+        }
+
+        pub fn classic() -> Board {
+            let mut squares = vec![Square::Plain; 40];
+            squares[4] = Square::Ladder(14);
+            squares[9] = Square::HatShop;
+            squares[13] = Square::Mud;
+            squares[17] = Square::Snake(6);
+            squares[21] = Square::Ladder(30);
+            squares[25] = Square::Thief;
+            squares[28] = Square::HatShop;
+            squares[33] = Square::Snake(19);
+            squares[36] = Square::Mud;
+            Board::new(squares)
+        }
+
+        pub fn finish(&self) -> usize {
+            self.squares.len() - 1
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Player {
+        pub name: String,
+        pub position: usize,
+        pub hats: u32,
+        pub skip_next_turn: bool,
+    }
+
+    // what a roll does; the tutorial's three stub functions
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Action {
+        AddFancyHat,
+        RemoveFancyHat,
+        MovePlayer(u8),
+    }
+
+    pub struct Game<D: Dice> {
+        board: Board,
+        players: Vec<Player>,
+        dice: D,
+        rules: HashMap<u8, Action>, // rolls without a rule move the player
+        turn: usize,
+        winner: Option<usize>,
+        log: Vec<String>,
+    }
+
+    impl<D: Dice> Game<D> {
+        pub fn new(board: Board, names: &[&str], dice: D) -> Game<D> {
+            assert!(!names.is_empty(), "a game needs at least one player");
+            let players = names
+                .iter()
+                .map(|name| Player { name: name.to_string(), position: 0, hats: 0, skip_next_turn: false })
+                .collect();
+            let mut rules = HashMap::new();
+            rules.insert(3, Action::AddFancyHat);
+            rules.insert(7, Action::RemoveFancyHat);
+            Game { board, players, dice, rules, turn: 0, winner: None, log: Vec::new() }
+        }
+
+        // replaces whatever the roll did before
+        pub fn on_roll(&mut self, roll: u8, action: Action) {
+            self.rules.insert(roll, action);
+        }
+
+        pub fn players(&self) -> &[Player] {
+            &self.players
+        }
+
+        pub fn winner(&self) -> Option<&Player> {
+            self.winner.map(|i| &self.players[i])
+        }
+
+        pub fn log(&self) -> &[String] {
+            &self.log
+        }
+
+        // plays one player's turn and returns the log lines it added
+        pub fn take_turn(&mut self) -> &[String] {
+            let start = self.log.len();
+            if self.winner.is_some() {
+                return &self.log[start..];
+            }
+            let i = self.turn;
+            self.turn = (self.turn + 1) % self.players.len();
+
+            if self.players[i].skip_next_turn {
+                self.players[i].skip_next_turn = false;
+                let line = format!("{} is stuck in the mud and misses a turn", self.players[i].name);
+                self.log.push(line);
+                return &self.log[start..];
+            }
+
+            let roll = self.dice.roll();
+            let action = self.rules.get(&roll).copied().unwrap_or(Action::MovePlayer(roll));
+            let finish = self.board.finish();
+            let player = &mut self.players[i];
+            let mut lines = vec![format!("{} rolls {}", player.name, roll)];
+            match action {
+                Action::AddFancyHat => {
+                    player.hats += 1;
+                    lines.push(format!("{} puts on a fancy hat ({} now)", player.name, player.hats));
+                }
+                Action::RemoveFancyHat => {
+                    if player.hats > 0 {
+                        player.hats -= 1;
+                        lines.push(format!("{} takes off a fancy hat ({} left)", player.name, player.hats));
+                    } else {
+                        lines.push(format!("{} has no hat to take off", player.name));
+                    }
+                }
+                Action::MovePlayer(spaces) => {
+                    player.position = (player.position + spaces as usize).min(finish);
+                    lines.push(format!("{} moves to square {}", player.name, player.position));
+                    match self.board.squares[player.position] {
+                        Square::Plain => (),
+                        Square::Ladder(to) => {
+                            player.position = to;
+                            lines.push(format!("{} climbs a ladder to square {}", player.name, to));
+                        }
+                        Square::Snake(to) => {
+                            player.position = to;
+                            lines.push(format!("{} slides down a snake to square {}", player.name, to));
+                        }
+                        Square::HatShop => {
+                            player.hats += 1;
+                            lines.push(format!("{} gets a free fancy hat ({} now)", player.name, player.hats));
+                        }
+                        Square::Thief => {
+                            if player.hats > 0 {
+                                player.hats -= 1;
+                                lines.push(format!("a thief steals one of {}'s hats", player.name));
+                            }
+                        }
+                        Square::Mud => {
+                            player.skip_next_turn = true;
+                            lines.push(format!("{} gets stuck in the mud", player.name));
+                        }
+                    }
+                    if player.position == finish {
+                        lines.push(format!("{} wins wearing {} fancy hat(s)!", player.name, player.hats));
+                        self.winner = Some(i);
+                    }
+                }
+            }
+            self.log.extend(lines);
+            &self.log[start..]
+        }
+    }
+
+    pub fn play_in_terminal(names: &[&str], seed: u64) -> io::Result<()> {
+        let mut game = Game::new(Board::classic(), names, SeededDice::new(seed));
+        let stdin = io::stdin();
+        let mut lines = stdin.lock().lines();
+        while game.winner().is_none() {
+            println!("Press Enter to roll...");
+            if lines.next().transpose()?.is_none() {
+                break; // stdin closed
+            }
+            for line in game.take_turn() {
+                println!("{}", line);
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        // start, ladder, snake, hat shop, mud and thief within reach of small rolls
+        fn small_board() -> Board {
+            use Square::*;
+            Board::new(vec![Plain, Plain, Ladder(6), Plain, HatShop, Snake(1), Plain, Mud, Thief, Plain])
+        }
+
+        #[test]
+        fn scripted_game() {
+            let mut game = Game::new(small_board(), &["A", "B"], FixedDice::new(vec![2, 5, 3, 6, 2, 4]));
+            while game.winner().is_none() {
+                game.take_turn();
+            }
+            assert_eq!(
+                game.log(),
+                [
+                    "A rolls 2",
+                    "A moves to square 2",
+                    "A climbs a ladder to square 6",
+                    "B rolls 5",
+                    "B moves to square 5",
+                    "B slides down a snake to square 1",
+                    "A rolls 3",
+                    "A puts on a fancy hat (1 now)",
+                    "B rolls 6",
+                    "B moves to square 7",
+                    "B gets stuck in the mud",
+                    "A rolls 2",
+                    "A moves to square 8",
+                    "a thief steals one of A's hats",
+                    "B is stuck in the mud and misses a turn",
+                    "A rolls 4",
+                    "A moves to square 9", // 12 is past the finish, which still counts
+                    "A wins wearing 0 fancy hat(s)!",
+                ]
+            );
+            assert_eq!(game.players()[1].position, 7);
+            assert!(game.take_turn().is_empty()); // nothing happens after a win
+        }
+
+        #[test]
+        fn custom_roll_rules() {
+            let mut game = Game::new(small_board(), &["A"], FixedDice::new(vec![7, 12]));
+            game.on_roll(12, Action::AddFancyHat);
+            assert_eq!(game.take_turn(), ["A rolls 7", "A has no hat to take off"]);
+            assert_eq!(game.take_turn(), ["A rolls 12", "A puts on a fancy hat (1 now)"]);
+            assert_eq!(game.players()[0].position, 0);
+        }
+
+        #[test]
+        fn seeded_classic_game() {
+            let play = |seed| {
+                let mut game = Game::new(Board::classic(), &["Ferris", "Corro"], SeededDice::new(seed));
+                while game.winner().is_none() {
+                    game.take_turn();
+                }
+                assert_eq!(game.winner().unwrap().position, game.board.finish());
+                game.log
+            };
+            let log = play(42);
+            assert_eq!(log, play(42)); // the same seed plays the same game
+            assert!(log.last().unwrap().contains("wins wearing"));
+
+            // landing on a ladder or snake always moves the player on
+            let squares = Board::classic().squares;
+            for (i, line) in log.iter().enumerate() {
+                let landed = match line.rsplit_once(" moves to square ") {
+                    Some((_, square)) => square.parse::<usize>().unwrap(),
+                    None => continue,
+                };
+                match squares[landed] {
+                    Square::Ladder(to) => assert!(log[i + 1].ends_with(&format!("climbs a ladder to square {}", to))),
+                    Square::Snake(to) => assert!(log[i + 1].ends_with(&format!("slides down a snake to square {}", to))),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
@@ -211,6 +211,1354 @@ fn main() {
     // Filename: src/front_of_house/hosting.rs:
     pub fn add_to_waitlist() {}
 
+    //--------------restaurant library--------------
+    // the stubs above, filled in (see `mod restaurant` below main)
+    use restaurant::back_of_house::{Appetizer, Breakfast, MenuItem};
+    use restaurant::Restaurant;
+    let mut place = Restaurant::new(&[2, 4, 6]);
+    place.add_to_waitlist("Ferris", 4, 0);
+    place.add_to_waitlist("Corro", 2, 5);
+    println!("{:?}", place.estimated_wait("Corro")); // Some(0), a 2-seat table is free
+    let seated = place.seat_next(10).unwrap(); // Ferris, at the 4-seat table
+    let order = place
+        .take_order(seated.table, vec![
+            MenuItem::Breakfast(Breakfast::summer("Rye")),
+            MenuItem::Appetizer(Appetizer::Soup),
+        ])
+        .unwrap();
+    place.cook_next(); // the kitchen works through orders in the order they came in
+    place.deliver(order).unwrap();
+    // wrong appetizer: swap it, recook and send it out again
+    place
+        .fix_incorrect_order(order, vec![
+            MenuItem::Breakfast(Breakfast::summer("Rye")),
+            MenuItem::Appetizer(Appetizer::Salad),
+        ])
+        .unwrap();
+    println!("{:?}", place.order(order).map(|o| &o.status)); // Some(Delivered { corrections: 1 })
+    println!("bill: {} cents", place.take_payment(seated.table).unwrap());
+
+    //--------------seasonal menu--------------
+    use restaurant::back_of_house::menu::{Menu, Season};
+    let menu: Menu = "
+        [prices]
+        breakfast = 1100
+        soup = 500
+        salad = 700
+
+        [toast]
+        rye = 0
+        brioche = 150
+
+        [spring]
+        fruit = [\"strawberries\"]
+        [summer]
+        fruit = [\"peaches\", \"apricots\"]
+        [autumn]
+        fruit = [\"apples\"]
+        [winter]
+        fruit = [\"oranges\"]
+        surcharge = 100
+    ".parse().unwrap(); // or Menu::load("menu.toml")
+    println!("{:?}", Season::from_date(7, 4)); // Ok(Summer)
+    println!("{:?}", menu.fruit_on(7, 4)); // the summer fruits take turns by day
+    let breakfast = menu.breakfast_on(1, 15, "Brioche").unwrap(); // oranges, winter surcharge
+    println!("{}: {} cents", breakfast.seasonal_fruit(), menu.price(&MenuItem::Breakfast(breakfast.clone())));
+    if let Err(e) = menu.breakfast(Season::Spring, "pumpernickel") {
+        println!("{}", e); // pumpernickel toast isn't on the menu
+    }
+    println!("{:?}", Breakfast::for_season(Season::Autumn, "wheat").map(|b| b.seasonal_fruit().to_string()));
+    let mut brunch = Restaurant::with_menu(&[4], menu);
+    brunch.add_to_waitlist("Ferris", 2, 0);
+    let table = brunch.seat_next(0).unwrap().table;
+    println!("{:?}", brunch.take_order(table, vec![MenuItem::Breakfast(Breakfast::summer("Wheat"))])); // Err(UnknownToast)
+
+    //--------------restaurant simulation--------------
+    use restaurant::simulation::{self, SimConfig};
+    let config = SimConfig { cooks: 1, seed: 7, ..SimConfig::default() };
+    println!("{}", simulation::run(&config)); // virtual clock, same numbers every time
+    let busier = SimConfig { arrival_every: 3.0, cooks: 3, ..config.clone() };
+    println!("{}", simulation::run(&busier));
+    // the same evening on threads, with a simulated minute lasting 2ms and three hosts
+    let staffed = SimConfig { hosts: 3, ..config.clone() };
+    println!("{}", simulation::run_threaded(&staffed, std::time::Duration::from_millis(2)));
+
 }
 
+mod restaurant {
+    use std::collections::{HashMap, VecDeque};
+    use std::fmt;
+
+    pub type OrderId = u32;
+    pub type Minutes = u32; // minutes since opening; the caller owns the clock
+
+    #[derive(Debug, PartialEq)]
+    pub enum RestaurantError {
+        NoSuchTable(u32),
+        TableNotSeated(u32),
+        NoSuchOrder(OrderId),
+        EmptyOrder,
+        NotCookedYet(OrderId),
+        NotDeliveredYet(OrderId),
+        AlreadyPaid(OrderId),
+        UnknownToast(String),
+    }
+
+    impl fmt::Display for RestaurantError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                RestaurantError::NoSuchTable(n) => write!(f, "there is no table {}", n),
+                RestaurantError::TableNotSeated(n) => write!(f, "nobody is sitting at table {}", n),
+                RestaurantError::NoSuchOrder(id) => write!(f, "there is no order {}", id),
+                RestaurantError::EmptyOrder => write!(f, "an order needs at least one item"),
+                RestaurantError::NotCookedYet(id) => write!(f, "order {} hasn't been cooked yet", id),
+                RestaurantError::NotDeliveredYet(id) => write!(f, "order {} hasn't been delivered yet", id),
+                RestaurantError::AlreadyPaid(id) => write!(f, "order {} has already been paid for", id),
+                RestaurantError::UnknownToast(toast) => write!(f, "{} toast isn't on the menu", toast),
+            }
+        }
+    }
+
+    impl std::error::Error for RestaurantError {}
+
+    pub mod front_of_house {
+        pub mod hosting {
+            use super::super::Minutes;
+            use std::collections::VecDeque;
+
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct Party {
+                pub name: String,
+                pub size: u32,
+                pub joined_at: Minutes,
+            }
+
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct Table {
+                pub number: u32,
+                pub seats: u32,
+                pub party: Option<Party>,
+            }
+
+            // how long a party usually keeps a table, for estimating waits
+            pub const TABLE_TURNOVER: Minutes = 45;
+
+            #[derive(Debug, Default)]
+            pub struct Waitlist {
+                parties: VecDeque<Party>,
+            }
+
+            impl Waitlist {
+                pub fn len(&self) -> usize {
+                    self.parties.len()
+                }
+
+                pub fn is_empty(&self) -> bool {
+                    self.parties.is_empty()
+                }
+
+                pub fn parties(&self) -> impl Iterator<Item = &Party> {
+                    self.parties.iter()
+                }
+
+                pub fn position(&self, name: &str) -> Option<usize> {
+                    self.parties.iter().position(|p| p.name == name)
+                }
+
+                pub fn remove(&mut self, name: &str) -> Option<Party> {
+                    let i = self.position(name)?;
+                    self.parties.remove(i)
+                }
+            }
+
+            // returns the party's place in line, starting at 0
+            pub fn add_to_waitlist(waitlist: &mut Waitlist, name: &str, size: u32, now: Minutes) -> usize {
+                waitlist.parties.push_back(Party { name: String::from(name), size, joined_at: now });
+                waitlist.parties.len() - 1
+            }
+
+            // only the tables big enough for the party matter, and only the
+            // parties ahead that could take one of them. If one of those
+            // tables is free for it the wait is nothing; after that every
+            // round of them costs one turnover. None if no table is big enough
+            pub fn estimated_wait(waitlist: &Waitlist, tables: &[Table], name: &str) -> Option<Minutes> {
+                let position = waitlist.position(name)?;
+                let size = waitlist.parties[position].size;
+                let fitting: Vec<&Table> = tables.iter().filter(|t| t.seats >= size).collect();
+                if fitting.is_empty() {
+                    return None;
+                }
+                let ahead = waitlist
+                    .parties
+                    .iter()
+                    .take(position)
+                    .filter(|p| fitting.iter().any(|t| t.seats >= p.size))
+                    .count() as u32;
+                let free = fitting.iter().filter(|t| t.party.is_none()).count() as u32;
+                if ahead < free {
+                    return Some(0);
+                }
+                let rounds = (ahead - free) / fitting.len() as u32 + 1;
+                Some(rounds * TABLE_TURNOVER)
+            }
+
+            // seats the first party in line that fits a free table, at the
+            // smallest table that fits so the big ones stay free for big
+            // parties; returns the table number
+            pub fn seat_at_table(waitlist: &mut Waitlist, tables: &mut [Table]) -> Option<u32> {
+                for i in 0..waitlist.parties.len() {
+                    let size = waitlist.parties[i].size;
+                    let table = tables
+                        .iter_mut()
+                        .filter(|t| t.party.is_none() && t.seats >= size)
+                        .min_by_key(|t| t.seats);
+                    if let Some(table) = table {
+                        table.party = waitlist.parties.remove(i);
+                        return Some(table.number);
+                    }
+                }
+                None
+            }
+        }
+
+        pub mod serving {
+            use super::super::back_of_house::menu::Menu;
+            use super::super::back_of_house::MenuItem;
+            use super::super::{Order, OrderId, OrderStatus};
+
+            pub fn take_order(id: OrderId, table: u32, items: Vec<MenuItem>) -> Order {
+                Order { id, table, items, status: OrderStatus::Taken, corrections: 0 }
+            }
+
+            pub fn take_payment<'a>(menu: &Menu, orders: impl Iterator<Item = &'a Order>) -> u32 {
+                orders
+                    .flat_map(|order| order.items.iter())
+                    .map(|item| menu.price(item))
+                    .sum()
+            }
+        }
+    }
+
+    pub mod back_of_house {
+        use super::{Order, OrderStatus, RestaurantError};
+
+        use menu::{Menu, MenuError, Season};
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Breakfast {
+            pub toast: String,
+            seasonal_fruit: String,
+            season: Season,
+        }
+
+        impl Breakfast {
+            // takes the toast as given; `Restaurant::take_order` checks it
+            // against the menu
+            pub fn summer(toast: &str) -> Breakfast {
+                Breakfast {
+                    toast: String::from(toast),
+                    seasonal_fruit: String::from(Menu::default().fruits(Season::Summer)[0].as_str()),
+                    season: Season::Summer,
+                }
+            }
+
+            // off the built-in menu; use `Menu::breakfast` for a loaded one
+            pub fn for_season(season: Season, toast: &str) -> Result<Breakfast, MenuError> {
+                Menu::default().breakfast(season, toast)
+            }
+
+            // customers can read what comes with it, but not change it
+            pub fn seasonal_fruit(&self) -> &str {
+                &self.seasonal_fruit
+            }
+
+            pub fn season(&self) -> Season {
+                self.season
+            }
+        }
+
+        #[derive(Debug, Clone, Copy, PartialEq)]
+        pub enum Appetizer {
+            Soup,
+            Salad,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum MenuItem {
+            Breakfast(Breakfast),
+            Appetizer(Appetizer),
+        }
+
+        impl MenuItem {
+            // minutes on the stove
+            pub fn cooking_time(&self) -> u32 {
+                match self {
+                    MenuItem::Breakfast(_) => 12,
+                    MenuItem::Appetizer(Appetizer::Soup) => 4,
+                    MenuItem::Appetizer(Appetizer::Salad) => 6,
+                }
+            }
+        }
+
+        // a paid order is closed; cooking it again would put it back on a bill
+        pub fn cook_order(order: &mut Order) -> Result<(), RestaurantError> {
+            if order.status == OrderStatus::Paid {
+                return Err(RestaurantError::AlreadyPaid(order.id));
+            }
+            order.status = OrderStatus::Cooked;
+            Ok(())
+        }
+
+        // swap in the right items, cook them again and send them back out
+        pub fn fix_incorrect_order(order: &mut Order, items: Vec<MenuItem>) -> Result<(), RestaurantError> {
+            if order.status == OrderStatus::Paid {
+                return Err(RestaurantError::AlreadyPaid(order.id));
+            }
+            order.items = items;
+            order.corrections += 1;
+            cook_order(order)?;
+            super::deliver_order(order);
+            Ok(())
+        }
+
+        // what's on offer and what it costs, per season; loaded from a small
+        // TOML file shaped like DEFAULT_MENU
+        pub mod menu {
+            use super::{Appetizer, Breakfast, MenuItem};
+            use std::fmt;
+            use std::fs;
+            use std::io;
+            use std::path::Path;
+            use std::str::FromStr;
+
+            pub const DEFAULT_MENU: &str = r#"
+# all prices in cents
+[prices]
+breakfast = 1250
+soup = 600
+salad = 750
+
+# toast and what it adds to the breakfast
+[toast]
+white = 0
+wheat = 0
+rye = 0
+sourdough = 75
+
+[spring]
+fruit = ["strawberries", "rhubarb"]
+surcharge = 0
+
+[summer]
+fruit = ["peaches", "blueberries", "cherries"]
+surcharge = 0
+
+[autumn]
+fruit = ["apples", "pears", "plums"]
+surcharge = 0
+
+[winter]
+fruit = ["oranges", "kiwis"]
+surcharge = 100
+"#;
+
+            #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            pub enum Season {
+                Spring,
+                Summer,
+                Autumn,
+                Winter,
+            }
+
+            impl Season {
+                pub const ALL: [Season; 4] = [Season::Spring, Season::Summer, Season::Autumn, Season::Winter];
+
+                pub fn name(self) -> &'static str {
+                    match self {
+                        Season::Spring => "spring",
+                        Season::Summer => "summer",
+                        Season::Autumn => "autumn",
+                        Season::Winter => "winter",
+                    }
+                }
+
+                // northern hemisphere, switching on the usual equinox and
+                // solstice dates (Mar 20, Jun 21, Sep 22, Dec 21)
+                pub fn from_date(month: u32, day: u32) -> Result<Season, MenuError> {
+                    day_of_year(month, day)?;
+                    let season = match (month, day) {
+                        (3, 20..) | (4..=5, _) | (6, ..=20) => Season::Spring,
+                        (6, _) | (7..=8, _) | (9, ..=21) => Season::Summer,
+                        (9, _) | (10..=11, _) | (12, ..=20) => Season::Autumn,
+                        _ => Season::Winter,
+                    };
+                    Ok(season)
+                }
+            }
+
+            impl fmt::Display for Season {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    write!(f, "{}", self.name())
+                }
+            }
+
+            impl FromStr for Season {
+                type Err = MenuError;
+
+                fn from_str(s: &str) -> Result<Season, MenuError> {
+                    Season::ALL
+                        .into_iter()
+                        .find(|season| season.name().eq_ignore_ascii_case(s.trim()))
+                        .ok_or_else(|| MenuError::UnknownSeason(s.to_string()))
+                }
+            }
+
+            // leap-year agnostic: Feb 29 counts as Mar 1
+            fn day_of_year(month: u32, day: u32) -> Result<u32, MenuError> {
+                const DAYS: [u32; 12] = [31, 29, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+                if !(1..=12).contains(&month) || day == 0 || day > DAYS[month as usize - 1] {
+                    return Err(MenuError::InvalidDate { month, day });
+                }
+                let before: u32 = DAYS[..month as usize - 1].iter().sum();
+                Ok(before + day - u32::from(month > 2))
+            }
+
+            #[derive(Debug)]
+            pub enum MenuError {
+                Io(io::Error),
+                Parse { line: usize, reason: String },
+                UnknownSeason(String),
+                UnknownToast(String),
+                NoFruit(Season),
+                InvalidDate { month: u32, day: u32 },
+            }
+
+            impl fmt::Display for MenuError {
+                fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                    match self {
+                        MenuError::Io(e) => write!(f, "I/O error: {}", e),
+                        MenuError::Parse { line, reason } => write!(f, "menu line {}: {}", line, reason),
+                        MenuError::UnknownSeason(name) => write!(f, "no season called {}", name),
+                        MenuError::UnknownToast(name) => write!(f, "{} toast isn't on the menu", name),
+                        MenuError::NoFruit(season) => write!(f, "no fruit listed for {}", season),
+                        MenuError::InvalidDate { month, day } => write!(f, "{}/{} is not a date", month, day),
+                    }
+                }
+            }
+
+            impl std::error::Error for MenuError {}
+
+            impl From<io::Error> for MenuError {
+                fn from(e: io::Error) -> MenuError {
+                    MenuError::Io(e)
+                }
+            }
+
+            #[derive(Debug, Clone, PartialEq)]
+            pub struct Menu {
+                breakfast: u32,
+                soup: u32,
+                salad: u32,
+                toasts: Vec<(String, u32)>, // name and surcharge, in file order
+                fruits: [Vec<String>; 4],   // indexed like Season::ALL
+                surcharges: [u32; 4],
+            }
+
+            impl Default for Menu {
+                fn default() -> Menu {
+                    DEFAULT_MENU.parse().expect("the built-in menu parses")
+                }
+            }
+
+            impl Menu {
+                pub fn load<P: AsRef<Path>>(path: P) -> Result<Menu, MenuError> {
+                    fs::read_to_string(path)?.parse()
+                }
+
+                pub fn toasts(&self) -> impl Iterator<Item = &str> {
+                    self.toasts.iter().map(|(name, _)| name.as_str())
+                }
+
+                // the menu's spelling of the toast, ignoring case
+                pub fn toast(&self, name: &str) -> Result<&str, MenuError> {
+                    self.toasts
+                        .iter()
+                        .find(|(toast, _)| toast.eq_ignore_ascii_case(name.trim()))
+                        .map(|(toast, _)| toast.as_str())
+                        .ok_or_else(|| MenuError::UnknownToast(name.to_string()))
+                }
+
+                pub fn fruits(&self, season: Season) -> &[String] {
+                    &self.fruits[season as usize]
+                }
+
+                // the season's fruits take turns, one per day
+                pub fn fruit_on(&self, month: u32, day: u32) -> Result<&str, MenuError> {
+                    let fruits = self.fruits(Season::from_date(month, day)?);
+                    let day = day_of_year(month, day)? as usize;
+                    Ok(&fruits[day % fruits.len()])
+                }
+
+                pub fn breakfast(&self, season: Season, toast: &str) -> Result<Breakfast, MenuError> {
+                    Ok(Breakfast {
+                        toast: self.toast(toast)?.to_string(),
+                        seasonal_fruit: self.fruits(season)[0].clone(),
+                        season,
+                    })
+                }
+
+                pub fn breakfast_on(&self, month: u32, day: u32, toast: &str) -> Result<Breakfast, MenuError> {
+                    Ok(Breakfast {
+                        toast: self.toast(toast)?.to_string(),
+                        seasonal_fruit: self.fruit_on(month, day)?.to_string(),
+                        season: Season::from_date(month, day)?,
+                    })
+                }
+
+                // toast that isn't on the menu adds nothing; take_order turns it away
+                pub fn price(&self, item: &MenuItem) -> u32 {
+                    match item {
+                        MenuItem::Breakfast(breakfast) => {
+                            let toast = self.toasts.iter().find(|(name, _)| name.eq_ignore_ascii_case(&breakfast.toast));
+                            self.breakfast + self.surcharges[breakfast.season as usize] + toast.map_or(0, |(_, extra)| *extra)
+                        }
+                        MenuItem::Appetizer(Appetizer::Soup) => self.soup,
+                        MenuItem::Appetizer(Appetizer::Salad) => self.salad,
+                    }
+                }
+            }
+
+            enum Value {
+                Number(u32),
+                List(Vec<String>),
+            }
+
+            // a `key = value` line, where value is a number or a list of strings
+            fn parse_line(line: &str) -> Result<(&str, Value), String> {
+                let (key, value) = line.split_once('=').ok_or("expected key = value")?;
+                let (key, value) = (key.trim(), value.trim());
+                if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-') {
+                    return Err(format!("bad key {:?}", key));
+                }
+                if let Some(inner) = value.strip_prefix('[').and_then(|v| v.strip_suffix(']')) {
+                    let items = inner
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|item| !item.is_empty())
+                        .map(|item| {
+                            item.strip_prefix('"')
+                                .and_then(|i| i.strip_suffix('"'))
+                                .filter(|i| !i.contains('"'))
+                                .map(String::from)
+                                .ok_or_else(|| format!("expected a quoted string, got {}", item))
+                        })
+                        .collect::<Result<_, _>>()?;
+                    return Ok((key, Value::List(items)));
+                }
+                value
+                    .replace('_', "")
+                    .parse()
+                    .map(|n| (key, Value::Number(n)))
+                    .map_err(|_| format!("expected a number or a list, got {}", value))
+            }
+
+            impl FromStr for Menu {
+                type Err = MenuError;
+
+                fn from_str(text: &str) -> Result<Menu, MenuError> {
+                    let mut menu = Menu {
+                        breakfast: 0,
+                        soup: 0,
+                        salad: 0,
+                        toasts: Vec::new(),
+                        fruits: Default::default(),
+                        surcharges: [0; 4],
+                    };
+                    let mut section = String::new();
+                    for (i, raw) in text.lines().enumerate() {
+                        let error = |reason: String| MenuError::Parse { line: i + 1, reason };
+                        // no strings hold a '#', so everything after one is a comment
+                        let line = raw.split('#').next().unwrap_or("").trim();
+                        if line.is_empty() {
+                            continue;
+                        }
+                        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                            section = name.trim().to_string();
+                            if section != "prices" && section != "toast" && section.parse::<Season>().is_err() {
+                                return Err(error(format!("unknown section [{}]", section)));
+                            }
+                            continue;
+                        }
+                        let (key, value) = parse_line(line).map_err(error)?;
+                        match (section.as_str(), key, value) {
+                            ("prices", "breakfast", Value::Number(n)) => menu.breakfast = n,
+                            ("prices", "soup", Value::Number(n)) => menu.soup = n,
+                            ("prices", "salad", Value::Number(n)) => menu.salad = n,
+                            ("toast", toast, Value::Number(n)) => {
+                                if menu.toast(toast).is_ok() {
+                                    return Err(error(format!("{} toast is listed twice", toast)));
+                                }
+                                menu.toasts.push((toast.to_string(), n));
+                            }
+                            (season, "fruit", Value::List(fruits)) if season.parse::<Season>().is_ok() => {
+                                menu.fruits[season.parse::<Season>()? as usize] = fruits;
+                            }
+                            (season, "surcharge", Value::Number(n)) if season.parse::<Season>().is_ok() => {
+                                menu.surcharges[season.parse::<Season>()? as usize] = n;
+                            }
+                            ("", _, _) => return Err(error(String::from("key outside of a section"))),
+                            (section, key, _) => return Err(error(format!("unexpected {} in [{}]", key, section))),
+                        }
+                    }
+                    if let Some(season) = Season::ALL.into_iter().find(|&s| menu.fruits(s).is_empty()) {
+                        return Err(MenuError::NoFruit(season));
+                    }
+                    Ok(menu)
+                }
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum OrderStatus {
+        Taken,
+        InKitchen,
+        Cooked,
+        Delivered { corrections: u32 },
+        Paid,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Order {
+        pub id: OrderId,
+        pub table: u32,
+        pub items: Vec<back_of_house::MenuItem>,
+        pub status: OrderStatus,
+        pub corrections: u32,
+    }
+
+    fn deliver_order(order: &mut Order) {
+        order.status = OrderStatus::Delivered { corrections: order.corrections };
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Seating {
+        pub table: u32,
+        pub party: String,
+        pub waited: Minutes,
+    }
 
+    // ties the modules together: one waitlist, the tables, the menu, the
+    // kitchen queue and every order taken so far
+    pub struct Restaurant {
+        waitlist: front_of_house::hosting::Waitlist,
+        menu: back_of_house::menu::Menu,
+        tables: Vec<front_of_house::hosting::Table>,
+        kitchen: VecDeque<OrderId>,
+        orders: HashMap<OrderId, Order>,
+        next_order: OrderId,
+    }
+
+    impl Restaurant {
+        // one table per entry, numbered from 1, with that many seats
+        pub fn new(table_sizes: &[u32]) -> Restaurant {
+            Restaurant::with_menu(table_sizes, Default::default())
+        }
+
+        pub fn with_menu(table_sizes: &[u32], menu: back_of_house::menu::Menu) -> Restaurant {
+            let tables = table_sizes
+                .iter()
+                .zip(1..)
+                .map(|(&seats, number)| front_of_house::hosting::Table { number, seats, party: None })
+                .collect();
+            Restaurant {
+                waitlist: Default::default(),
+                menu,
+                tables,
+                kitchen: VecDeque::new(),
+                orders: HashMap::new(),
+                next_order: 1,
+            }
+        }
+
+        pub fn add_to_waitlist(&mut self, name: &str, size: u32, now: Minutes) -> usize {
+            front_of_house::hosting::add_to_waitlist(&mut self.waitlist, name, size, now)
+        }
+
+        pub fn leave_waitlist(&mut self, name: &str) -> Option<front_of_house::hosting::Party> {
+            self.waitlist.remove(name)
+        }
+
+        pub fn estimated_wait(&self, name: &str) -> Option<Minutes> {
+            front_of_house::hosting::estimated_wait(&self.waitlist, &self.tables, name)
+        }
+
+        pub fn waitlist(&self) -> &front_of_house::hosting::Waitlist {
+            &self.waitlist
+        }
+
+        pub fn tables(&self) -> &[front_of_house::hosting::Table] {
+            &self.tables
+        }
+
+        pub fn menu(&self) -> &back_of_house::menu::Menu {
+            &self.menu
+        }
+
+        fn check_items(&self, items: &[back_of_house::MenuItem]) -> Result<(), RestaurantError> {
+            if items.is_empty() {
+                return Err(RestaurantError::EmptyOrder);
+            }
+            for item in items {
+                if let back_of_house::MenuItem::Breakfast(breakfast) = item {
+                    self.menu
+                        .toast(&breakfast.toast)
+                        .map_err(|_| RestaurantError::UnknownToast(breakfast.toast.clone()))?;
+                }
+            }
+            Ok(())
+        }
+
+        pub fn seat_next(&mut self, now: Minutes) -> Option<Seating> {
+            let number = front_of_house::hosting::seat_at_table(&mut self.waitlist, &mut self.tables)?;
+            let party = self.table(number).ok()?.party.as_ref()?;
+            Some(Seating {
+                table: number,
+                party: party.name.clone(),
+                waited: now.saturating_sub(party.joined_at),
+            })
+        }
+
+        fn table(&self, number: u32) -> Result<&front_of_house::hosting::Table, RestaurantError> {
+            self.tables
+                .iter()
+                .find(|t| t.number == number)
+                .ok_or(RestaurantError::NoSuchTable(number))
+        }
+
+        pub fn take_order(&mut self, table: u32, items: Vec<back_of_house::MenuItem>) -> Result<OrderId, RestaurantError> {
+            if self.table(table)?.party.is_none() {
+                return Err(RestaurantError::TableNotSeated(table));
+            }
+            self.check_items(&items)?;
+            let id = self.next_order;
+            self.next_order += 1;
+            let mut order = front_of_house::serving::take_order(id, table, items);
+            order.status = OrderStatus::InKitchen;
+            self.orders.insert(id, order);
+            self.kitchen.push_back(id);
+            Ok(id)
+        }
+
+        pub fn order(&self, id: OrderId) -> Option<&Order> {
+            self.orders.get(&id)
+        }
+
+        pub fn kitchen_queue(&self) -> impl Iterator<Item = &Order> {
+            self.kitchen.iter().filter_map(|id| self.orders.get(id))
+        }
+
+        // cooks the oldest order waiting in the kitchen; paid orders never
+        // stay queued, so anything popped here is still open
+        pub fn cook_next(&mut self) -> Option<OrderId> {
+            let id = self.kitchen.pop_front()?;
+            back_of_house::cook_order(self.orders.get_mut(&id)?).ok()?;
+            Some(id)
+        }
+
+        pub fn deliver(&mut self, id: OrderId) -> Result<(), RestaurantError> {
+            let order = self.orders.get_mut(&id).ok_or(RestaurantError::NoSuchOrder(id))?;
+            if order.status != OrderStatus::Cooked {
+                return Err(RestaurantError::NotCookedYet(id));
+            }
+            deliver_order(order);
+            Ok(())
+        }
+
+        pub fn fix_incorrect_order(&mut self, id: OrderId, items: Vec<back_of_house::MenuItem>) -> Result<(), RestaurantError> {
+            self.check_items(&items)?;
+            let order = self.orders.get_mut(&id).ok_or(RestaurantError::NoSuchOrder(id))?;
+            back_of_house::fix_incorrect_order(order, items)?;
+            self.kitchen.retain(|&queued| queued != id);
+            Ok(())
+        }
+
+        // totals the table's unpaid orders and frees the table; refuses while
+        // any of them is still on its way, so nothing is billed before it's served
+        pub fn take_payment(&mut self, table: u32) -> Result<u32, RestaurantError> {
+            if self.table(table)?.party.is_none() {
+                return Err(RestaurantError::TableNotSeated(table));
+            }
+            let unpaid = |o: &Order| o.table == table && o.status != OrderStatus::Paid;
+            if let Some(pending) = self
+                .orders
+                .values()
+                .filter(|o| unpaid(o))
+                .find(|o| !matches!(o.status, OrderStatus::Delivered { .. }))
+            {
+                return Err(RestaurantError::NotDeliveredYet(pending.id));
+            }
+            let total = front_of_house::serving::take_payment(&self.menu, self.orders.values().filter(|o| unpaid(o)));
+            let mut paid = Vec::new();
+            for order in self.orders.values_mut().filter(|o| unpaid(o)) {
+                order.status = OrderStatus::Paid;
+                paid.push(order.id);
+            }
+            self.kitchen.retain(|queued| !paid.contains(queued));
+            for t in self.tables.iter_mut().filter(|t| t.number == table) {
+                t.party = None;
+            }
+            Ok(total)
+        }
+    }
+
+    // a discrete-event model of a sitting: parties arrive, wait for a table,
+    // order, get their food and leave. `run` plays it out on a virtual clock,
+    // `run_threaded` with a thread per host and per cook and real (scaled) time
+    pub mod simulation {
+        use super::back_of_house::menu::{Menu, Season};
+        use super::back_of_house::{Appetizer, MenuItem};
+        use super::front_of_house::hosting::{self, Table, Waitlist};
+        use super::Minutes;
+        use std::cmp::Reverse;
+        use std::collections::{BinaryHeap, HashMap, VecDeque};
+        use std::fmt;
+        use std::sync::mpsc::{self, RecvTimeoutError};
+        use std::sync::{Arc, Mutex};
+        use std::thread;
+        use std::time::{Duration, Instant};
+
+        #[derive(Debug, Clone)]
+        pub struct SimConfig {
+            pub tables: Vec<u32>,     // seats per table
+            pub arrival_every: f64,   // mean minutes between parties
+            pub max_party: u32,       // party sizes are uniform in 1..=max_party
+            pub cooks: usize,
+            pub hosts: usize, // seating and serving at once; only `run_threaded` has more than one
+            pub delivery_time: Minutes, // kitchen pass to table
+            pub dining_time: Minutes,   // from food on the table to leaving
+            pub open_for: Minutes,      // no arrivals after this
+            pub season: Season,
+            pub seed: u64,
+        }
+
+        impl Default for SimConfig {
+            fn default() -> SimConfig {
+                SimConfig {
+                    tables: vec![2, 2, 2, 4, 4, 4, 6, 6],
+                    arrival_every: 8.0,
+                    max_party: 6,
+                    cooks: 2,
+                    hosts: 2,
+                    delivery_time: 2,
+                    dining_time: 30,
+                    open_for: 180,
+                    season: Season::Summer,
+                    seed: 1,
+                }
+            }
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Metrics {
+            pub parties_seated: usize,
+            pub turned_away: usize, // no table is big enough
+            pub average_wait: f64,  // arrival to seated
+            pub longest_wait: Minutes,
+            pub average_service: f64, // seated to food on the table
+            pub table_utilisation: f64, // share of table-minutes occupied
+            pub revenue: u32,
+            pub closed_at: Minutes,
+        }
+
+        impl fmt::Display for Metrics {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                writeln!(f, "parties seated:    {} ({} turned away)", self.parties_seated, self.turned_away)?;
+                writeln!(f, "wait for a table:  {:.1} min average, {} min longest", self.average_wait, self.longest_wait)?;
+                writeln!(f, "wait for food:     {:.1} min average", self.average_service)?;
+                writeln!(f, "table utilisation: {:.1}%", self.table_utilisation * 100.0)?;
+                writeln!(f, "revenue:           {} cents", self.revenue)?;
+                write!(f, "closed at:         minute {}", self.closed_at)
+            }
+        }
+
+        // xorshift64*, so a seed always gives the same evening
+        struct Rng(u64);
+
+        impl Rng {
+            fn new(seed: u64) -> Rng {
+                Rng(seed.max(1))
+            }
+
+            fn next(&mut self) -> u64 {
+                self.0 ^= self.0 >> 12;
+                self.0 ^= self.0 << 25;
+                self.0 ^= self.0 >> 27;
+                self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+            }
+
+            // in [0, 1)
+            fn unit(&mut self) -> f64 {
+                (self.next() >> 11) as f64 / (1u64 << 53) as f64
+            }
+
+            fn below(&mut self, n: usize) -> usize {
+                (self.next() % n as u64) as usize
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        struct Arrival {
+            name: String,
+            size: u32,
+            at: Minutes,
+        }
+
+        // exponential gaps between parties, i.e. a Poisson arrival process
+        fn arrivals(config: &SimConfig, rng: &mut Rng) -> Vec<Arrival> {
+            let mut list = Vec::new();
+            let mut clock = 0.0;
+            loop {
+                clock += -config.arrival_every * (1.0 - rng.unit()).ln();
+                if clock > config.open_for as f64 {
+                    return list;
+                }
+                list.push(Arrival {
+                    name: format!("party {}", list.len() + 1),
+                    size: 1 + rng.below(config.max_party.max(1) as usize) as u32,
+                    at: clock as Minutes,
+                });
+            }
+        }
+
+        #[derive(Debug, Clone)]
+        struct Ticket {
+            table: u32,
+            items: Vec<MenuItem>,
+            seated_at: Minutes,
+        }
+
+        impl Ticket {
+            // a cook works the whole order at once; the slowest dish sets the pace
+            fn cooking_time(&self) -> Minutes {
+                self.items.iter().map(MenuItem::cooking_time).max().unwrap_or(0)
+            }
+        }
+
+        // the host's side of the evening, shared by both ways of running it
+        struct Floor {
+            waitlist: Waitlist,
+            tables: Vec<Table>,
+            menu: Menu,
+            season: Season,
+            rng: Rng,
+            occupied_since: HashMap<u32, Minutes>,
+            table_minutes: u64,
+            waits: Vec<Minutes>,
+            service: Vec<Minutes>,
+            turned_away: usize,
+            revenue: u32,
+        }
+
+        impl Floor {
+            fn new(config: &SimConfig, rng: Rng) -> Floor {
+                Floor {
+                    waitlist: Waitlist::default(),
+                    tables: config
+                        .tables
+                        .iter()
+                        .zip(1..)
+                        .map(|(&seats, number)| Table { number, seats, party: None })
+                        .collect(),
+                    menu: Menu::default(),
+                    season: config.season,
+                    rng,
+                    occupied_since: HashMap::new(),
+                    table_minutes: 0,
+                    waits: Vec::new(),
+                    service: Vec::new(),
+                    turned_away: 0,
+                    revenue: 0,
+                }
+            }
+
+            fn arrive(&mut self, arrival: &Arrival) {
+                if self.tables.iter().any(|t| t.seats >= arrival.size) {
+                    hosting::add_to_waitlist(&mut self.waitlist, &arrival.name, arrival.size, arrival.at);
+                } else {
+                    self.turned_away += 1;
+                }
+            }
+
+            // seats everyone who fits and hands back their orders
+            fn seat(&mut self, now: Minutes) -> Vec<Ticket> {
+                let mut tickets = Vec::new();
+                while let Some(number) = hosting::seat_at_table(&mut self.waitlist, &mut self.tables) {
+                    let table = self.tables.iter().find(|t| t.number == number).unwrap();
+                    let party = table.party.as_ref().unwrap();
+                    self.waits.push(now.saturating_sub(party.joined_at));
+                    let size = party.size;
+                    self.occupied_since.insert(number, now);
+                    tickets.push(Ticket { table: number, items: self.order(size), seated_at: now });
+                }
+                tickets
+            }
+
+            // a breakfast each, and a starter for the table half the time
+            fn order(&mut self, size: u32) -> Vec<MenuItem> {
+                let toasts: Vec<String> = self.menu.toasts().map(String::from).collect();
+                let mut items: Vec<MenuItem> = (0..size)
+                    .filter_map(|_| {
+                        let toast = &toasts[self.rng.below(toasts.len())];
+                        self.menu.breakfast(self.season, toast).ok()
+                    })
+                    .map(MenuItem::Breakfast)
+                    .collect();
+                match self.rng.below(4) {
+                    0 => items.push(MenuItem::Appetizer(Appetizer::Soup)),
+                    1 => items.push(MenuItem::Appetizer(Appetizer::Salad)),
+                    _ => {}
+                }
+                items
+            }
+
+            fn delivered(&mut self, ticket: &Ticket, now: Minutes) {
+                self.service.push(now.saturating_sub(ticket.seated_at));
+                self.revenue += ticket.items.iter().map(|item| self.menu.price(item)).sum::<u32>();
+            }
+
+            fn leave(&mut self, table: u32, now: Minutes) {
+                if let Some(since) = self.occupied_since.remove(&table) {
+                    self.table_minutes += u64::from(now.saturating_sub(since));
+                }
+                for t in self.tables.iter_mut().filter(|t| t.number == table) {
+                    t.party = None;
+                }
+            }
+
+            fn is_idle(&self) -> bool {
+                self.waitlist.is_empty() && self.occupied_since.is_empty()
+            }
+
+            fn metrics(&self, closed_at: Minutes) -> Metrics {
+                let average = |v: &[Minutes]| {
+                    if v.is_empty() {
+                        0.0
+                    } else {
+                        v.iter().map(|&m| m as f64).sum::<f64>() / v.len() as f64
+                    }
+                };
+                let capacity = self.tables.len() as f64 * closed_at as f64;
+                Metrics {
+                    parties_seated: self.waits.len(),
+                    turned_away: self.turned_away,
+                    average_wait: average(&self.waits),
+                    longest_wait: self.waits.iter().copied().max().unwrap_or(0),
+                    average_service: average(&self.service),
+                    table_utilisation: if capacity > 0.0 { self.table_minutes as f64 / capacity } else { 0.0 },
+                    revenue: self.revenue,
+                    closed_at,
+                }
+            }
+        }
+
+        enum Event {
+            Arrive(Arrival),
+            Cooked(Ticket),
+            Delivered(Ticket),
+            Leave(u32),
+        }
+
+        // events by due minute; ties go in the order they were added
+        #[derive(Default)]
+        struct Agenda {
+            queue: BinaryHeap<Reverse<(Minutes, u64)>>,
+            events: HashMap<u64, Event>,
+            added: u64,
+        }
+
+        impl Agenda {
+            fn add(&mut self, at: Minutes, event: Event) {
+                self.added += 1;
+                self.queue.push(Reverse((at, self.added)));
+                self.events.insert(self.added, event);
+            }
+
+            fn next_due(&self) -> Option<Minutes> {
+                self.queue.peek().map(|&Reverse((at, _))| at)
+            }
+
+            fn pop(&mut self) -> Option<(Minutes, Event)> {
+                let Reverse((at, id)) = self.queue.pop()?;
+                Some((at, self.events.remove(&id)?))
+            }
+
+            fn is_empty(&self) -> bool {
+                self.queue.is_empty()
+            }
+        }
+
+        // single-threaded, jumping the clock from one event to the next
+        pub fn run(config: &SimConfig) -> Metrics {
+            let mut rng = Rng::new(config.seed);
+            let schedule = arrivals(config, &mut rng);
+            let mut floor = Floor::new(config, rng);
+
+            let mut agenda = Agenda::default();
+            for arrival in schedule {
+                agenda.add(arrival.at, Event::Arrive(arrival));
+            }
+
+            let mut kitchen: VecDeque<Ticket> = VecDeque::new();
+            let mut free_cooks = config.cooks.max(1);
+            let mut now = 0;
+            while let Some((at, event)) = agenda.pop() {
+                now = at;
+                let mut new_tickets = Vec::new();
+                match event {
+                    Event::Arrive(arrival) => {
+                        floor.arrive(&arrival);
+                        new_tickets = floor.seat(now);
+                    }
+                    Event::Cooked(ticket) => {
+                        free_cooks += 1;
+                        agenda.add(now + config.delivery_time, Event::Delivered(ticket));
+                    }
+                    Event::Delivered(ticket) => {
+                        floor.delivered(&ticket, now);
+                        agenda.add(now + config.dining_time, Event::Leave(ticket.table));
+                    }
+                    Event::Leave(table) => {
+                        floor.leave(table, now);
+                        new_tickets = floor.seat(now);
+                    }
+                }
+                kitchen.extend(new_tickets);
+                while free_cooks > 0 {
+                    let Some(ticket) = kitchen.pop_front() else { break };
+                    free_cooks -= 1;
+                    agenda.add(now + ticket.cooking_time(), Event::Cooked(ticket));
+                }
+            }
+            floor.metrics(now)
+        }
+
+        enum ToHost {
+            Arrive(Arrival),
+            Cooked(Ticket),
+        }
+
+        // what the hosts share: the floor, their timers and what's still to come
+        struct Shift {
+            floor: Floor,
+            timers: Agenda,
+            arrivals_left: usize,
+            in_kitchen: usize,
+            closed: bool,
+        }
+
+        impl Shift {
+            fn send_to_kitchen(&mut self, tickets: Vec<Ticket>, kitchen: &mpsc::Sender<Ticket>) {
+                self.in_kitchen += tickets.len();
+                for ticket in tickets {
+                    let _ = kitchen.send(ticket);
+                }
+            }
+
+            // deliveries and tables clearing that are due by `now`
+            fn run_timers(&mut self, now: Minutes, dining_time: Minutes, kitchen: &mpsc::Sender<Ticket>) {
+                while self.timers.next_due().is_some_and(|due| due <= now) {
+                    match self.timers.pop() {
+                        Some((_, Event::Delivered(ticket))) => {
+                            self.floor.delivered(&ticket, now);
+                            self.timers.add(now + dining_time, Event::Leave(ticket.table));
+                        }
+                        Some((_, Event::Leave(table))) => {
+                            self.floor.leave(table, now);
+                            let tickets = self.floor.seat(now);
+                            self.send_to_kitchen(tickets, kitchen);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            fn handle(&mut self, message: ToHost, now: Minutes, delivery_time: Minutes, kitchen: &mpsc::Sender<Ticket>) {
+                match message {
+                    ToHost::Arrive(arrival) => {
+                        self.arrivals_left -= 1;
+                        self.floor.arrive(&arrival);
+                        let tickets = self.floor.seat(now);
+                        self.send_to_kitchen(tickets, kitchen);
+                    }
+                    ToHost::Cooked(ticket) => {
+                        self.in_kitchen -= 1;
+                        self.timers.add(now + delivery_time, Event::Delivered(ticket));
+                    }
+                }
+            }
+
+            fn is_over(&self) -> bool {
+                self.arrivals_left == 0 && self.in_kitchen == 0 && self.timers.is_empty() && self.floor.is_idle()
+            }
+        }
+
+        // the same evening on real threads: one feeding arrivals, one per
+        // cook pulling tickets off a shared channel, and one per host taking
+        // turns at a shared inbox and keeping the timers. `minute` is how long
+        // a simulated minute really takes, so results drift a little from `run`'s
+        pub fn run_threaded(config: &SimConfig, minute: Duration) -> Metrics {
+            let mut rng = Rng::new(config.seed);
+            let schedule = arrivals(config, &mut rng);
+            let shift = Arc::new(Mutex::new(Shift {
+                arrivals_left: schedule.len(),
+                floor: Floor::new(config, rng),
+                timers: Agenda::default(),
+                in_kitchen: 0,
+                closed: false,
+            }));
+
+            let start = Instant::now();
+            let clock = move || (start.elapsed().as_secs_f64() / minute.as_secs_f64()) as Minutes;
+            let at = move |m: Minutes| start + minute * m;
+
+            let (to_host, inbox) = mpsc::channel();
+            let door = {
+                let to_host = to_host.clone();
+                thread::spawn(move || {
+                    for arrival in schedule {
+                        thread::sleep(at(arrival.at).saturating_duration_since(Instant::now()));
+                        if to_host.send(ToHost::Arrive(arrival)).is_err() {
+                            return;
+                        }
+                    }
+                })
+            };
+
+            let (to_kitchen, tickets) = mpsc::channel::<Ticket>();
+            let tickets = Arc::new(Mutex::new(tickets));
+            let cooks: Vec<_> = (0..config.cooks.max(1))
+                .map(|_| {
+                    let tickets = Arc::clone(&tickets);
+                    let to_host = to_host.clone();
+                    thread::spawn(move || loop {
+                        // the lock is dropped before cooking, so cooks work in parallel
+                        let ticket = match tickets.lock().unwrap().recv() {
+                            Ok(ticket) => ticket,
+                            Err(_) => return,
+                        };
+                        thread::sleep(minute * ticket.cooking_time());
+                        if to_host.send(ToHost::Cooked(ticket)).is_err() {
+                            return;
+                        }
+                    })
+                })
+                .collect();
+            drop(to_host);
+
+            let inbox = Arc::new(Mutex::new(inbox));
+            let (delivery_time, dining_time) = (config.delivery_time, config.dining_time);
+            let hosts: Vec<_> = (0..config.hosts.max(1))
+                .map(|_| {
+                    let (shift, inbox, to_kitchen) = (Arc::clone(&shift), Arc::clone(&inbox), to_kitchen.clone());
+                    thread::spawn(move || loop {
+                        let wait = {
+                            let mut shift = shift.lock().unwrap();
+                            shift.run_timers(clock(), dining_time, &to_kitchen);
+                            if shift.closed || shift.is_over() {
+                                shift.closed = true;
+                                return;
+                            }
+                            // at most a minute, in case another host adds an earlier timer meanwhile
+                            shift
+                                .timers
+                                .next_due()
+                                .map_or(minute, |due| at(due).saturating_duration_since(Instant::now()))
+                                .min(minute)
+                        };
+                        // one host waits at the inbox at a time, like the cooks at the
+                        // ticket channel; the shift isn't locked while they wait
+                        let message = match inbox.lock().unwrap().recv_timeout(wait) {
+                            Ok(message) => message,
+                            Err(RecvTimeoutError::Timeout) => continue,
+                            Err(RecvTimeoutError::Disconnected) => return,
+                        };
+                        shift.lock().unwrap().handle(message, clock(), delivery_time, &to_kitchen);
+                    })
+                })
+                .collect();
+            drop(to_kitchen);
+
+            for host in hosts {
+                let _ = host.join();
+            }
+            // every host's ticket sender is gone now, so the cooks finish up
+            for cook in cooks {
+                let _ = cook.join();
+            }
+            let _ = door.join();
+            let metrics = shift.lock().unwrap().floor.metrics(clock());
+            metrics
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::back_of_house::{Appetizer, MenuItem};
+        use super::*;
+
+        #[test]
+        fn paid_orders_stay_paid() {
+            let mut place = Restaurant::new(&[2]);
+            place.add_to_waitlist("Ferris", 2, 0);
+            let table = place.seat_next(0).unwrap().table;
+            let soup = place.take_order(table, vec![MenuItem::Appetizer(Appetizer::Soup)]).unwrap();
+            // still in the kitchen, so there's nothing to bill yet
+            assert_eq!(place.take_payment(table), Err(RestaurantError::NotDeliveredYet(soup)));
+            assert_eq!(place.cook_next(), Some(soup));
+            place.deliver(soup).unwrap();
+            assert_eq!(place.take_payment(table), Ok(600));
+
+            // the kitchen has nothing left of Ferris's, and the paid order can't be reopened
+            assert_eq!(place.cook_next(), None);
+            assert_eq!(place.order(soup).map(|o| &o.status), Some(&OrderStatus::Paid));
+            assert_eq!(
+                place.fix_incorrect_order(soup, vec![MenuItem::Appetizer(Appetizer::Salad)]),
+                Err(RestaurantError::AlreadyPaid(soup))
+            );
+
+            // the next party at the table pays for their own food only
+            place.add_to_waitlist("Corro", 1, 50);
+            assert_eq!(place.seat_next(50).map(|s| s.table), Some(table));
+            let salad = place.take_order(table, vec![MenuItem::Appetizer(Appetizer::Salad)]).unwrap();
+            assert_eq!(place.cook_next(), Some(salad));
+            place.deliver(salad).unwrap();
+            assert_eq!(place.take_payment(table), Ok(750));
+            assert_eq!(place.order(soup).map(|o| &o.status), Some(&OrderStatus::Paid));
+        }
+
+        #[test]
+        fn waits_depend_on_party_size() {
+            let mut place = Restaurant::new(&[2, 2, 6]);
+            place.add_to_waitlist("Ferris", 2, 0);
+            place.add_to_waitlist("Corro", 2, 0);
+            place.seat_next(0);
+            place.seat_next(0);
+            place.add_to_waitlist("Big", 6, 0);
+            place.add_to_waitlist("Small", 2, 0);
+            place.add_to_waitlist("Huge", 8, 0);
+            // the 6-seat table is still free for Big, and Big is ahead of Small for it
+            assert_eq!(place.estimated_wait("Big"), Some(0));
+            assert_eq!(place.estimated_wait("Small"), Some(front_of_house::hosting::TABLE_TURNOVER));
+            // no table seats eight
+            assert_eq!(place.estimated_wait("Huge"), None);
+
+            place.seat_next(0);
+            place.leave_waitlist("Small");
+            // the two-seaters don't help a party of six, and Huge isn't
+            // competing for the six-seater
+            place.add_to_waitlist("Another six", 6, 0);
+            assert_eq!(place.estimated_wait("Another six"), Some(front_of_house::hosting::TABLE_TURNOVER));
+        }
+    }
+}
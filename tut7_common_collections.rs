@@ -1,4 +1,40 @@
 fn main() {
+    // `words`, `bench`, `scores` and `directory` run one of the commands
+    // below main instead of the examples
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("words") => {
+            if let Err(e) = word_stats::run(args.into_iter().skip(1)) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some("bench") => {
+            hashing::run_benchmarks(&hashing::Bencher::default());
+            return;
+        }
+        Some("scores") => {
+            if let Err(e) = scoreboard::run(args.into_iter().skip(1), &mut std::io::stdout()) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some("directory") => {
+            let mut staff = exercises::directory::Directory::new();
+            if let Err(e) = staff.run_interactive(std::io::stdin().lock(), &mut std::io::stdout()) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+            return;
+        }
+        Some(other) => {
+            eprintln!("unknown command {}; expected words, bench, scores or directory", other);
+            std::process::exit(2);
+        }
+        None => {}
+    }
 
     //--------------vector--------------
     let v: Vec<i32> = Vec::new();
@@ -133,7 +169,2059 @@ fn main() {
     /*
     By default, HashMap uses a hashing function called SipHash that can provide resistance to Denial of Service (DoS) attacks involving hash tables1. This is not the fastest hashing algorithm available, but the trade-off for better security that comes with the drop in performance is worth it. If you profile your code and find that the default hash function is too slow for your purposes, you can switch to another function by specifying a different hasher. A hasher is a type that implements the BuildHasher trait. We’ll talk about traits and how to implement them in Chapter 10. You don’t necessarily have to implement your own hasher from scratch; crates.io has libraries shared by other Rust users that provide hashers implementing many common hashing algorithms.
     */
+    
+    //--------------spreadsheet--------------
+    // SpreadsheetCell grown into a working sheet (see `mod spreadsheet` below main)
+    use spreadsheet::{Export, Sheet};
+    let mut sheet = Sheet::new();
+    sheet.set("A1", "Item").unwrap();
+    sheet.set("B1", "Price").unwrap();
+    sheet.set("A2", "blue paint").unwrap();
+    sheet.set("B2", "10.12").unwrap();
+    sheet.set("A3", "brushes").unwrap();
+    sheet.set("B3", "3").unwrap();
+    sheet.set("B4", "=SUM(B2:B3)").unwrap();
+    sheet.set("B5", "=B4 * 2 - MAX(B2:B3) / 2").unwrap();
+    println!("{:?}", sheet.get("B4")); // Some(Ok(Float(13.12)))
+    println!("{:?}", sheet.set("B3", "5").map(|cells| cells.len())); // Ok(3): B3, then B4 and B5 that read it
+    if let Err(e) = sheet.set("B2", "=B5 + 1") {
+        println!("{}", e); // circular reference: B2 -> B5 -> B4 -> B2
+    }
+    sheet.set("C2", "=A2 * 2").unwrap();
+    if let Some(Err(e)) = sheet.get("C2") {
+        println!("{} ({})", e, e.explain()); // #VALUE! (expected a number, found text "blue paint")
+    }
+    let csv = sheet.to_csv(Export::Formulas);
+    print!("{}", csv);
+    let copy = Sheet::from_csv(&csv).unwrap();
+    print!("{}", copy.to_csv(Export::Values));
 
+    
+    //--------------word statistics--------------
+    // the word counter above, grown into a command (see `mod word_stats` below main):
+    // $ tut7 words --top 5 --default-stop-words --format json book.txt
+    let options = word_stats::Options { top: 3, ..Default::default() };
+    let mut counter = word_stats::Counter::new();
+    let text = "Hello world, wonderful world. Hello, World! The world is wonderful.";
+    counter.feed(text.as_bytes(), &options).unwrap(); // any BufRead: a file, stdin, ...
+    word_stats::report(&counter, &options, &mut std::io::stdout()).unwrap();
+    
+    //--------------custom hashers--------------
+    // the BuildHasher swap described above (see `mod hashing` below main)
+    use hashing::{FnvBuildHasher, FxBuildHasher, ScoreTable};
+    let counts = hashing::word_count::<FxBuildHasher>("hello world wonderful world");
+    println!("{:?}", counts.get("world")); // Some(2)
+    let mut scores: ScoreTable<FnvBuildHasher> = ScoreTable::with_hasher(Default::default());
+    scores.set("Blue", 10);
+    scores.set_default("Yellow", 50);
+    scores.set_default("Blue", 50); // already there, stays 10
+    scores.add("Blue", 15);
+    println!("{:?}", scores.ranking()); // [("Yellow", 50), ("Blue", 25)]
+    let mut sip = ScoreTable::new(); // SipHash, like a plain HashMap
+    println!("{}", sip.is_empty()); // true
+    sip.add("Blue", 10);
+    println!("{} and {} teams", sip.len(), scores.len()); // 1 and 2 teams
+    // $ tut7 bench   times SipHash, FNV and Fx on the workloads above
+    
+    //--------------scoreboard--------------
+    // the scores map above, with history (see `mod scoreboard` below main)
+    // $ tut7 scores add Blue 10
+    // $ tut7 scores --file league.txt rank
+    let mut board = scoreboard::Scoreboard::new();
+    board.add("Blue", 10).unwrap();
+    board.add("Yellow", 50).unwrap();
+    board.next_round();
+    board.add("Blue", 40).unwrap();
+    board.subtract("Yellow", 5).unwrap();
+    board.add("Red", 50).unwrap();
+    for standing in board.ranking() {
+        println!("{}. {} {}", standing.rank, standing.team, standing.score); // Blue and Red share 1st
+    }
+    let mut other = scoreboard::Scoreboard::new();
+    other.add("Green", 7).unwrap();
+    board.merge(&other).unwrap();
+    println!("{:?}", board.undo(2)); // Green's 7, then Red's 50
+    println!("{:?}", board.rounds()); // {1: {Blue: 10, Yellow: 50}, 2: {Blue: 40, Yellow: -5}}
+    
+    //--------------exercises--------------
+    // the chapter's exercises (see `mod exercises` below main)
+    use exercises::{directory::Directory, pig_latin, stats};
+    let numbers = vec![3, 1, 4, 1, 5, 9, 2, 6];
+    println!("{:?} {:?}", stats::median(&numbers), stats::mode(&numbers)); // Some(3.5) Some(1)
+    println!("{}", pig_latin::translate("Hello, first apple! Ñandú señor")); // Ello-hay, irst-fay apple-hay! Andú-ñay eñor-say
+    let mut staff = Directory::new();
+    let commands = "Add Sally to Engineering\nadd Amir to Sales\nAdd Bob to Engineering\nList\nRemove Amir from Sales\nList Sales\n";
+    staff.run_interactive(commands.as_bytes(), &mut std::io::stdout()).unwrap();
+    // $ tut7 directory   reads the same commands from the terminal
 }
 
+mod spreadsheet {
+    use std::collections::{HashMap, HashSet};
+    use std::fmt;
+    use std::str::FromStr;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum SpreadsheetCell {
+        Int(i64),
+        Float(f64),
+        Text(String),
+    }
+
+    impl fmt::Display for SpreadsheetCell {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                SpreadsheetCell::Int(n) => write!(f, "{}", n),
+                SpreadsheetCell::Float(x) => write!(f, "{}", x),
+                SpreadsheetCell::Text(s) => write!(f, "{}", s),
+            }
+        }
+    }
+
+    impl SpreadsheetCell {
+        // what a typed-in value means: whole numbers, then decimals, then text
+        pub fn parse(input: &str) -> SpreadsheetCell {
+            let trimmed = input.trim();
+            if let Ok(n) = trimmed.parse() {
+                SpreadsheetCell::Int(n)
+            } else if let Some(x) = trimmed.parse::<f64>().ok().filter(|x| x.is_finite()) {
+                SpreadsheetCell::Float(x)
+            } else {
+                SpreadsheetCell::Text(input.to_string())
+            }
+        }
+
+        fn type_name(&self) -> &'static str {
+            match self {
+                SpreadsheetCell::Int(_) => "an integer",
+                SpreadsheetCell::Float(_) => "a number",
+                SpreadsheetCell::Text(_) => "text",
+            }
+        }
+    }
+
+    // a formula's error becomes that cell's value, and spreads to every
+    // cell that reads it
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum CellError {
+        DivByZero,
+        Type { expected: &'static str, found: String },
+        Overflow,
+    }
+
+    impl fmt::Display for CellError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                CellError::DivByZero => write!(f, "#DIV/0!"),
+                CellError::Type { .. } => write!(f, "#VALUE!"),
+                CellError::Overflow => write!(f, "#NUM!"),
+            }
+        }
+    }
+
+    impl CellError {
+        pub fn explain(&self) -> String {
+            match self {
+                CellError::DivByZero => String::from("division by zero"),
+                CellError::Type { expected, found } => format!("expected {}, found {}", expected, found),
+                CellError::Overflow => String::from("the result doesn't fit in a cell"),
+            }
+        }
+    }
+
+    pub type CellValue = Result<SpreadsheetCell, CellError>;
+
+    // errors from changing the sheet; the sheet is left as it was
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum SheetError {
+        BadReference(String),
+        Parse { formula: String, reason: String },
+        Cycle(Vec<CellRef>),
+        Csv { line: usize, reason: String },
+    }
+
+    impl fmt::Display for SheetError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                SheetError::BadReference(r) => write!(f, "{:?} is not a cell reference", r),
+                SheetError::Parse { formula, reason } => write!(f, "can't parse {}: {}", formula, reason),
+                SheetError::Cycle(path) => {
+                    let path: Vec<String> = path.iter().map(CellRef::to_string).collect();
+                    write!(f, "circular reference: {}", path.join(" -> "))
+                }
+                SheetError::Csv { line, reason } => write!(f, "CSV line {}: {}", line, reason),
+            }
+        }
+    }
+
+    impl std::error::Error for SheetError {}
+
+    // zero-based column and row; written A1-style, columns run A..Z, AA..
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+    pub struct CellRef {
+        pub row: u32,
+        pub col: u32,
+    }
+
+    impl CellRef {
+        pub fn new(col: u32, row: u32) -> CellRef {
+            CellRef { row, col }
+        }
+    }
+
+    impl FromStr for CellRef {
+        type Err = SheetError;
+
+        fn from_str(s: &str) -> Result<CellRef, SheetError> {
+            let bad = || SheetError::BadReference(s.to_string());
+            let split = s.find(|c: char| !c.is_ascii_alphabetic()).ok_or_else(bad)?;
+            let (letters, digits) = s.split_at(split);
+            if letters.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(bad());
+            }
+            let mut col: u32 = 0;
+            for b in letters.bytes() {
+                let digit = u32::from(b.to_ascii_uppercase() - b'A') + 1;
+                col = col.checked_mul(26).and_then(|c| c.checked_add(digit)).ok_or_else(bad)?;
+            }
+            let row: u32 = digits.parse().map_err(|_| bad())?;
+            if row == 0 {
+                return Err(bad());
+            }
+            Ok(CellRef::new(col - 1, row - 1))
+        }
+    }
+
+    impl fmt::Display for CellRef {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            let mut letters = Vec::new();
+            let mut n = self.col + 1;
+            while n > 0 {
+                letters.push(b'A' + ((n - 1) % 26) as u8);
+                n = (n - 1) / 26;
+            }
+            letters.reverse();
+            write!(f, "{}{}", String::from_utf8(letters).unwrap(), self.row + 1)
+        }
+    }
+
+    // a rectangle of cells, corners in any order
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct Range {
+        pub top_left: CellRef,
+        pub bottom_right: CellRef,
+    }
+
+    impl Range {
+        pub fn new(a: CellRef, b: CellRef) -> Range {
+            Range {
+                top_left: CellRef::new(a.col.min(b.col), a.row.min(b.row)),
+                bottom_right: CellRef::new(a.col.max(b.col), a.row.max(b.row)),
+            }
+        }
+
+        pub fn contains(&self, cell: CellRef) -> bool {
+            (self.top_left.col..=self.bottom_right.col).contains(&cell.col)
+                && (self.top_left.row..=self.bottom_right.row).contains(&cell.row)
+        }
+    }
+
+    impl fmt::Display for Range {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}:{}", self.top_left, self.bottom_right)
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Func {
+        Sum,
+        Avg,
+        Min,
+        Max,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Expr {
+        Value(SpreadsheetCell),
+        Ref(CellRef),
+        Range(Range), // only as a function argument
+        Neg(Box<Expr>),
+        Binary(char, Box<Expr>, Box<Expr>),
+        Call(Func, Vec<Expr>),
+    }
+
+    impl Expr {
+        // every cell the formula reads on its own, and every range it reads;
+        // ranges stay whole, SUM(A1:ZZZ999999) covers billions of cells
+        fn references(&self, cells: &mut Vec<CellRef>, ranges: &mut Vec<Range>) {
+            match self {
+                Expr::Value(_) => {}
+                Expr::Ref(cell) => cells.push(*cell),
+                Expr::Range(range) => ranges.push(*range),
+                Expr::Neg(e) => e.references(cells, ranges),
+                Expr::Binary(_, a, b) => {
+                    a.references(cells, ranges);
+                    b.references(cells, ranges);
+                }
+                Expr::Call(_, args) => args.iter().for_each(|arg| arg.references(cells, ranges)),
+            }
+        }
+    }
+
+    // recursive descent over
+    //   expr   = term (('+' | '-') term)*
+    //   term   = factor (('*' | '/') factor)*
+    //   factor = '-' factor | number | "text" | ref | NAME '(' args ')' | '(' expr ')'
+    //   args   = (ref ':' ref | expr) (',' ...)*
+    struct Parser<'a> {
+        chars: std::iter::Peekable<std::str::Chars<'a>>,
+    }
+
+    impl Parser<'_> {
+        fn skip_spaces(&mut self) {
+            while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        }
+
+        fn eat(&mut self, expected: char) -> bool {
+            self.skip_spaces();
+            self.chars.next_if_eq(&expected).is_some()
+        }
+
+        fn expr(&mut self) -> Result<Expr, String> {
+            let mut left = self.term()?;
+            loop {
+                self.skip_spaces();
+                match self.chars.next_if(|&c| c == '+' || c == '-') {
+                    Some(op) => left = Expr::Binary(op, Box::new(left), Box::new(self.term()?)),
+                    None => return Ok(left),
+                }
+            }
+        }
+
+        fn term(&mut self) -> Result<Expr, String> {
+            let mut left = self.factor()?;
+            loop {
+                self.skip_spaces();
+                match self.chars.next_if(|&c| c == '*' || c == '/') {
+                    Some(op) => left = Expr::Binary(op, Box::new(left), Box::new(self.factor()?)),
+                    None => return Ok(left),
+                }
+            }
+        }
+
+        fn factor(&mut self) -> Result<Expr, String> {
+            self.skip_spaces();
+            match self.chars.peek() {
+                Some('-') => {
+                    self.chars.next();
+                    Ok(Expr::Neg(Box::new(self.factor()?)))
+                }
+                Some('(') => {
+                    self.chars.next();
+                    let inner = self.expr()?;
+                    if !self.eat(')') {
+                        return Err(String::from("missing )"));
+                    }
+                    Ok(inner)
+                }
+                Some('"') => {
+                    self.chars.next();
+                    let mut text = String::new();
+                    loop {
+                        match self.chars.next() {
+                            Some('"') if self.chars.next_if_eq(&'"').is_some() => text.push('"'),
+                            Some('"') => return Ok(Expr::Value(SpreadsheetCell::Text(text))),
+                            Some(c) => text.push(c),
+                            None => return Err(String::from("unterminated text")),
+                        }
+                    }
+                }
+                Some(c) if c.is_ascii_digit() || *c == '.' => {
+                    let mut number = String::new();
+                    while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                        number.push(c);
+                    }
+                    match SpreadsheetCell::parse(&number) {
+                        SpreadsheetCell::Text(_) => Err(format!("bad number {}", number)),
+                        value => Ok(Expr::Value(value)),
+                    }
+                }
+                Some(c) if c.is_ascii_alphabetic() => {
+                    let mut word = String::new();
+                    while let Some(c) = self.chars.next_if(|c| c.is_ascii_alphanumeric()) {
+                        word.push(c);
+                    }
+                    if self.eat('(') {
+                        self.call(&word)
+                    } else {
+                        word.parse().map(Expr::Ref).map_err(|_| format!("unknown name {}", word))
+                    }
+                }
+                Some(c) => Err(format!("unexpected {:?}", c)),
+                None => Err(String::from("unexpected end of formula")),
+            }
+        }
+
+        fn call(&mut self, name: &str) -> Result<Expr, String> {
+            let func = match name.to_ascii_uppercase().as_str() {
+                "SUM" => Func::Sum,
+                "AVG" | "AVERAGE" => Func::Avg,
+                "MIN" => Func::Min,
+                "MAX" => Func::Max,
+                _ => return Err(format!("unknown function {}", name)),
+            };
+            let mut args = Vec::new();
+            if self.eat(')') {
+                return Ok(Expr::Call(func, args));
+            }
+            loop {
+                let arg = self.expr()?;
+                match arg {
+                    Expr::Ref(from) if self.eat(':') => match self.factor()? {
+                        Expr::Ref(to) => args.push(Expr::Range(Range::new(from, to))),
+                        _ => return Err(String::from("a range needs a cell on both sides of :")),
+                    },
+                    arg => args.push(arg),
+                }
+                if self.eat(')') {
+                    return Ok(Expr::Call(func, args));
+                }
+                if !self.eat(',') {
+                    return Err(format!("expected , or ) in {}(...)", name));
+                }
+            }
+        }
+    }
+
+    pub fn parse_formula(formula: &str) -> Result<Expr, SheetError> {
+        let error = |reason: String| SheetError::Parse { formula: formula.to_string(), reason };
+        let mut parser = Parser { chars: formula.chars().peekable() };
+        let expr = parser.expr().map_err(error)?;
+        parser.skip_spaces();
+        match parser.chars.next() {
+            None => Ok(expr),
+            Some(c) => Err(error(format!("unexpected {:?}", c))),
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum Input {
+        Value(SpreadsheetCell),
+        Formula { source: String, expr: Expr },
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Entry {
+        input: Input,
+        value: CellValue,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Export {
+        Values,
+        Formulas,
+    }
+
+    #[derive(Debug, Default)]
+    pub struct Sheet {
+        cells: HashMap<CellRef, Entry>,
+        // who reads each cell, so a change knows what to recalculate
+        dependents: HashMap<CellRef, HashSet<CellRef>>,
+        // and the ranges each formula reads, kept whole rather than per cell
+        range_readers: HashMap<CellRef, Vec<Range>>,
+    }
+
+    impl Sheet {
+        pub fn new() -> Sheet {
+            Sheet::default()
+        }
+
+        // `=...` is a formula, an empty string clears the cell; returns every
+        // cell whose value was recalculated, in the order they were
+        pub fn set(&mut self, cell: &str, input: &str) -> Result<Vec<CellRef>, SheetError> {
+            let cell: CellRef = cell.trim().parse()?;
+            self.set_cell(cell, input)
+        }
+
+        pub fn set_cell(&mut self, cell: CellRef, input: &str) -> Result<Vec<CellRef>, SheetError> {
+            let new = match input.strip_prefix('=') {
+                Some(source) => {
+                    let expr = parse_formula(source)?;
+                    let (mut reads, mut ranges) = (Vec::new(), Vec::new());
+                    expr.references(&mut reads, &mut ranges);
+                    if ranges.iter().any(|range| range.contains(cell)) {
+                        return Err(SheetError::Cycle(vec![cell, cell]));
+                    }
+                    reads.extend(self.cells_in(&ranges));
+                    if let Some(path) = self.find_cycle(cell, &reads) {
+                        return Err(SheetError::Cycle(path));
+                    }
+                    Some(Input::Formula { source: source.trim().to_string(), expr })
+                }
+                None if input.is_empty() => None,
+                None => Some(Input::Value(SpreadsheetCell::parse(input))),
+            };
+
+            for read in self.references(cell).0 {
+                if let Some(readers) = self.dependents.get_mut(&read) {
+                    readers.remove(&cell);
+                }
+            }
+            self.range_readers.remove(&cell);
+            match new {
+                Some(input) => {
+                    self.cells.insert(cell, Entry { input, value: Ok(SpreadsheetCell::Int(0)) });
+                    let (reads, ranges) = self.references(cell);
+                    for read in reads {
+                        self.dependents.entry(read).or_default().insert(cell);
+                    }
+                    if !ranges.is_empty() {
+                        self.range_readers.insert(cell, ranges);
+                    }
+                }
+                None => {
+                    self.cells.remove(&cell);
+                }
+            }
+            Ok(self.recalculate(cell))
+        }
+
+        fn references(&self, cell: CellRef) -> (Vec<CellRef>, Vec<Range>) {
+            let (mut reads, mut ranges) = (Vec::new(), Vec::new());
+            if let Some(Entry { input: Input::Formula { expr, .. }, .. }) = self.cells.get(&cell) {
+                expr.references(&mut reads, &mut ranges);
+            }
+            (reads, ranges)
+        }
+
+        // the cells in use inside any of `ranges`, row by row; the empty
+        // ones can't lead anywhere, so there's no need to visit them
+        fn cells_in(&self, ranges: &[Range]) -> Vec<CellRef> {
+            let mut cells: Vec<CellRef> =
+                self.cells.keys().copied().filter(|&cell| ranges.iter().any(|range| range.contains(cell))).collect();
+            cells.sort();
+            cells
+        }
+
+        // every cell the formula in `cell` reads that could hold a value
+        fn precedents(&self, cell: CellRef) -> Vec<CellRef> {
+            let (mut reads, ranges) = self.references(cell);
+            reads.extend(self.cells_in(&ranges));
+            reads
+        }
+
+        // the formulas reading `cell`, directly or through a range, in order
+        fn readers(&self, cell: CellRef) -> Vec<CellRef> {
+            let mut readers: Vec<CellRef> = self.dependents.get(&cell).into_iter().flatten().copied().collect();
+            readers.extend(
+                self.range_readers
+                    .iter()
+                    .filter(|(_, ranges)| ranges.iter().any(|range| range.contains(cell)))
+                    .map(|(&reader, _)| reader),
+            );
+            readers.sort();
+            readers.dedup();
+            readers
+        }
+
+        // would `cell` reading `reads` make it depend on itself? if so,
+        // returns the loop, starting and ending at `cell`
+        fn find_cycle(&self, cell: CellRef, reads: &[CellRef]) -> Option<Vec<CellRef>> {
+            fn walk(sheet: &Sheet, at: CellRef, target: CellRef, seen: &mut HashSet<CellRef>, path: &mut Vec<CellRef>) -> bool {
+                path.push(at);
+                if at == target {
+                    return true;
+                }
+                if seen.insert(at) {
+                    for next in sheet.precedents(at) {
+                        if walk(sheet, next, target, seen, path) {
+                            return true;
+                        }
+                    }
+                }
+                path.pop();
+                false
+            }
+            let mut seen = HashSet::new();
+            for &read in reads {
+                let mut path = vec![cell];
+                if walk(self, read, cell, &mut seen, &mut path) {
+                    return Some(path);
+                }
+            }
+            None
+        }
+
+        // `changed` and everything downstream of it, each after the cells it reads
+        fn recalculate(&mut self, changed: CellRef) -> Vec<CellRef> {
+            fn visit(sheet: &Sheet, cell: CellRef, seen: &mut HashSet<CellRef>, order: &mut Vec<CellRef>) {
+                if !seen.insert(cell) {
+                    return;
+                }
+                for reader in sheet.readers(cell) {
+                    visit(sheet, reader, seen, order);
+                }
+                order.push(cell);
+            }
+            let mut order = Vec::new();
+            visit(self, changed, &mut HashSet::new(), &mut order);
+            order.reverse();
+            for &cell in &order {
+                let value = match self.cells.get(&cell) {
+                    Some(Entry { input: Input::Formula { expr, .. }, .. }) => self.eval(expr),
+                    Some(Entry { input: Input::Value(value), .. }) => Ok(value.clone()),
+                    None => continue,
+                };
+                if let Some(entry) = self.cells.get_mut(&cell) {
+                    entry.value = value;
+                }
+            }
+            order.retain(|cell| self.cells.contains_key(cell));
+            order
+        }
+
+        pub fn get(&self, cell: &str) -> Option<&CellValue> {
+            self.value(cell.parse().ok()?)
+        }
+
+        pub fn value(&self, cell: CellRef) -> Option<&CellValue> {
+            self.cells.get(&cell).map(|entry| &entry.value)
+        }
+
+        // what was typed in, formulas with their leading `=`
+        pub fn input(&self, cell: CellRef) -> Option<String> {
+            self.cells.get(&cell).map(|entry| match &entry.input {
+                Input::Value(value) => value.to_string(),
+                Input::Formula { source, .. } => format!("={}", source),
+            })
+        }
+
+        // one past the last used column and row
+        pub fn extent(&self) -> (u32, u32) {
+            self.cells
+                .keys()
+                .fold((0, 0), |(cols, rows), cell| (cols.max(cell.col + 1), rows.max(cell.row + 1)))
+        }
+
+        fn eval(&self, expr: &Expr) -> CellValue {
+            use SpreadsheetCell::{Float, Int};
+            match expr {
+                Expr::Value(value) => Ok(value.clone()),
+                // an empty cell counts as 0
+                Expr::Ref(cell) => self.value(*cell).cloned().unwrap_or(Ok(Int(0))),
+                Expr::Range(range) => Err(CellError::Type { expected: "a single value", found: format!("the range {}", range) }),
+                Expr::Neg(inner) => match number(self.eval(inner)?)? {
+                    Int(n) => n.checked_neg().map(Int).ok_or(CellError::Overflow),
+                    other => Ok(Float(-as_float(&other))),
+                },
+                Expr::Binary(op, a, b) => {
+                    let (a, b) = (number(self.eval(a)?)?, number(self.eval(b)?)?);
+                    if let (Int(x), Int(y)) = (&a, &b) {
+                        let (x, y) = (*x, *y);
+                        return match op {
+                            '+' => x.checked_add(y).map(Int).ok_or(CellError::Overflow),
+                            '-' => x.checked_sub(y).map(Int).ok_or(CellError::Overflow),
+                            '*' => x.checked_mul(y).map(Int).ok_or(CellError::Overflow),
+                            _ if y == 0 => Err(CellError::DivByZero),
+                            // whole results stay whole; only i64::MIN / -1 overflows
+                            _ => match x.checked_rem(y) {
+                                None => Err(CellError::Overflow),
+                                Some(0) => x.checked_div(y).map(Int).ok_or(CellError::Overflow),
+                                Some(_) => Ok(Float(x as f64 / y as f64)),
+                            },
+                        };
+                    }
+                    let (x, y) = (as_float(&a), as_float(&b));
+                    let result = match op {
+                        '+' => x + y,
+                        '-' => x - y,
+                        '*' => x * y,
+                        _ if y == 0.0 => return Err(CellError::DivByZero),
+                        _ => x / y,
+                    };
+                    if result.is_finite() {
+                        Ok(Float(result))
+                    } else {
+                        Err(CellError::Overflow)
+                    }
+                }
+                Expr::Call(func, args) => {
+                    // ranges skip empty cells; everything else must be a number
+                    let mut values = Vec::new();
+                    for arg in args {
+                        match arg {
+                            Expr::Range(range) => {
+                                for cell in self.cells_in(&[*range]) {
+                                    if let Some(value) = self.value(cell) {
+                                        values.push(number(value.clone()?)?);
+                                    }
+                                }
+                            }
+                            arg => values.push(number(self.eval(arg)?)?),
+                        }
+                    }
+                    aggregate(*func, &values)
+                }
+            }
+        }
+
+        pub fn to_csv(&self, export: Export) -> String {
+            let (cols, rows) = self.extent();
+            let mut out = String::new();
+            for row in 0..rows {
+                let fields: Vec<String> = (0..cols)
+                    .map(|col| {
+                        let cell = CellRef::new(col, row);
+                        let text = match (export, self.value(cell)) {
+                            (Export::Formulas, _) => self.input(cell).unwrap_or_default(),
+                            (Export::Values, Some(Ok(value))) => value.to_string(),
+                            (Export::Values, Some(Err(e))) => e.to_string(),
+                            (Export::Values, None) => String::new(),
+                        };
+                        csv_field(&text)
+                    })
+                    .collect();
+                out.push_str(&fields.join(","));
+                out.push('\n');
+            }
+            out
+        }
+
+        // fields are typed in as if by hand, so `=...` fields become formulas
+        pub fn from_csv(text: &str) -> Result<Sheet, SheetError> {
+            let mut sheet = Sheet::new();
+            for (row, record) in csv_records(text)?.into_iter().enumerate() {
+                for (col, field) in record.iter().enumerate() {
+                    if !field.is_empty() {
+                        sheet.set_cell(CellRef::new(col as u32, row as u32), field).map_err(|e| SheetError::Csv {
+                            line: row + 1,
+                            reason: e.to_string(),
+                        })?;
+                    }
+                }
+            }
+            Ok(sheet)
+        }
+    }
+
+    fn number(value: SpreadsheetCell) -> CellValue {
+        match value {
+            SpreadsheetCell::Text(_) => Err(CellError::Type { expected: "a number", found: format!("{} {:?}", value.type_name(), value.to_string()) }),
+            number => Ok(number),
+        }
+    }
+
+    fn as_float(value: &SpreadsheetCell) -> f64 {
+        match value {
+            SpreadsheetCell::Int(n) => *n as f64,
+            SpreadsheetCell::Float(x) => *x,
+            SpreadsheetCell::Text(_) => f64::NAN,
+        }
+    }
+
+    fn aggregate(func: Func, values: &[SpreadsheetCell]) -> CellValue {
+        use SpreadsheetCell::{Float, Int};
+        let all_ints = values.iter().all(|v| matches!(v, Int(_)));
+        let ints = || values.iter().filter_map(|v| if let Int(n) = v { Some(*n) } else { None });
+        let floats = || values.iter().map(as_float);
+        match func {
+            Func::Sum if all_ints => ints().try_fold(0i64, i64::checked_add).map(Int).ok_or(CellError::Overflow),
+            Func::Sum => Ok(Float(floats().sum())),
+            Func::Avg if values.is_empty() => Err(CellError::DivByZero),
+            Func::Avg => Ok(Float(floats().sum::<f64>() / values.len() as f64)),
+            // like other spreadsheets, MIN and MAX of nothing are 0
+            Func::Min | Func::Max if values.is_empty() => Ok(Int(0)),
+            Func::Min if all_ints => Ok(Int(ints().min().unwrap())),
+            Func::Max if all_ints => Ok(Int(ints().max().unwrap())),
+            Func::Min => Ok(Float(floats().fold(f64::INFINITY, f64::min))),
+            Func::Max => Ok(Float(floats().fold(f64::NEG_INFINITY, f64::max))),
+        }
+    }
+
+    // RFC 4180: quote fields holding commas, quotes or line breaks
+    fn csv_field(text: &str) -> String {
+        if text.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", text.replace('"', "\"\""))
+        } else {
+            text.to_string()
+        }
+    }
+
+    fn csv_records(text: &str) -> Result<Vec<Vec<String>>, SheetError> {
+        let mut records = Vec::new();
+        let mut record = Vec::new();
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut line = 1;
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' if quoted && chars.next_if_eq(&'"').is_some() => field.push('"'),
+                '"' if quoted => quoted = false,
+                '"' if field.is_empty() => quoted = true,
+                '"' => return Err(SheetError::Csv { line, reason: String::from("stray quote in an unquoted field") }),
+                ',' if !quoted => record.push(std::mem::take(&mut field)),
+                '\r' if !quoted && chars.peek() == Some(&'\n') => {}
+                '\n' if !quoted => {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                    line += 1;
+                }
+                c => {
+                    if c == '\n' {
+                        line += 1;
+                    }
+                    field.push(c);
+                }
+            }
+        }
+        if quoted {
+            return Err(SheetError::Csv { line, reason: String::from("unterminated quoted field") });
+        }
+        if !field.is_empty() || !record.is_empty() {
+            record.push(field);
+            records.push(record);
+        }
+        Ok(records)
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use SpreadsheetCell::{Float, Int};
+
+        fn value(sheet: &Sheet, cell: &str) -> CellValue {
+            sheet.get(cell).cloned().unwrap()
+        }
+
+        #[test]
+        fn arithmetic_overflow() {
+            let mut sheet = Sheet::new();
+            sheet.set("A1", &i64::MIN.to_string()).unwrap();
+            sheet.set("B1", "=A1 / -1").unwrap();
+            sheet.set("B2", "=A1 - 1").unwrap();
+            sheet.set("B3", "=-A1").unwrap();
+            sheet.set("B4", "=A1 * 2").unwrap();
+            sheet.set("B5", "=SUM(A1, A1)").unwrap();
+            for cell in ["B1", "B2", "B3", "B4", "B5"] {
+                assert_eq!(value(&sheet, cell), Err(CellError::Overflow), "{}", cell);
+            }
+            sheet.set("B6", "=A1 / 1").unwrap();
+            assert_eq!(value(&sheet, "B6"), Ok(Int(i64::MIN)));
+        }
+
+        #[test]
+        fn division() {
+            let mut sheet = Sheet::new();
+            sheet.set("A1", "7").unwrap();
+            sheet.set("A2", "0").unwrap();
+            sheet.set("B1", "=A1 / A2").unwrap();
+            sheet.set("B2", "=A1 / 0.0").unwrap();
+            sheet.set("B3", "=AVG(C1:C3)").unwrap(); // nothing there
+            sheet.set("B4", "=B1 + 1").unwrap(); // errors spread
+            for cell in ["B1", "B2", "B3", "B4"] {
+                assert_eq!(value(&sheet, cell), Err(CellError::DivByZero), "{}", cell);
+            }
+            sheet.set("C1", "=A1 / 7").unwrap();
+            sheet.set("C2", "=A1 / 2").unwrap();
+            assert_eq!(value(&sheet, "C1"), Ok(Int(1)));
+            assert_eq!(value(&sheet, "C2"), Ok(Float(3.5)));
+        }
+
+        #[test]
+        fn cycles_are_rejected() {
+            let mut sheet = Sheet::new();
+            sheet.set("A1", "=B1 + 1").unwrap();
+            sheet.set("B1", "=C1 + 1").unwrap();
+            let path = |cells: &[&str]| cells.iter().map(|c| c.parse().unwrap()).collect::<Vec<CellRef>>();
+            assert_eq!(sheet.set("C1", "=A1"), Err(SheetError::Cycle(path(&["C1", "A1", "B1", "C1"]))));
+            assert_eq!(sheet.set("D1", "=D1"), Err(SheetError::Cycle(path(&["D1", "D1"]))));
+            assert_eq!(sheet.set("C2", "=SUM(A1:C3)"), Err(SheetError::Cycle(path(&["C2", "C2"]))));
+            // the sheet is left as it was
+            assert_eq!(sheet.get("C1"), None);
+            assert_eq!(value(&sheet, "A1"), Ok(Int(2)));
+        }
+
+        #[test]
+        fn dependents_are_recalculated() {
+            let mut sheet = Sheet::new();
+            sheet.set("A1", "1").unwrap();
+            sheet.set("A2", "=A1 * 10").unwrap();
+            sheet.set("A3", "=A2 + A1").unwrap();
+            sheet.set("B1", "=SUM(A1:A3)").unwrap();
+            assert_eq!(value(&sheet, "B1"), Ok(Int(22)));
+
+            let changed = sheet.set("A1", "2").unwrap();
+            let order: Vec<String> = changed.iter().map(CellRef::to_string).collect();
+            assert_eq!(order, ["A1", "A2", "A3", "B1"]); // each after the cells it reads
+            assert_eq!(value(&sheet, "A3"), Ok(Int(22)));
+            assert_eq!(value(&sheet, "B1"), Ok(Int(44)));
+
+            // a replaced formula stops listening to what it used to read
+            sheet.set("A2", "5").unwrap();
+            assert_eq!(sheet.set("A1", "3").unwrap().len(), 3); // A1, A3 and B1
+            assert_eq!(value(&sheet, "B1"), Ok(Int(16)));
+        }
+
+        #[test]
+        fn huge_ranges_are_not_expanded() {
+            let mut sheet = Sheet::new();
+            sheet.set("A1", "=SUM(B1:ZZ999999)").unwrap();
+            assert_eq!(value(&sheet, "A1"), Ok(Int(0)));
+            sheet.set("ZZ999999", "5").unwrap();
+            sheet.set("A2", "=A1 * 2").unwrap();
+            let changed: Vec<String> = sheet.set("B1", "1").unwrap().iter().map(CellRef::to_string).collect();
+            assert_eq!(changed, ["B1", "A1", "A2"]);
+            assert_eq!(value(&sheet, "A2"), Ok(Int(12)));
+            assert!(matches!(sheet.set("C5", "=MAX(A1:ZZ999999)"), Err(SheetError::Cycle(_)))); // C5 is inside
+            sheet.set("A1", "").unwrap(); // the range goes with the formula
+            assert_eq!(sheet.set("B1", "2").unwrap().len(), 1);
+        }
+    }
+}
+
+mod word_stats {
+    use std::collections::{HashMap, HashSet, VecDeque};
+    use std::fmt;
+    use std::fs::File;
+    use std::io::{self, BufRead, BufReader, Write};
+
+    // a small English list for --default-stop-words
+    pub const STOP_WORDS: &[&str] = &[
+        "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "has", "have", "he", "her", "his", "i",
+        "in", "is", "it", "its", "of", "on", "or", "she", "so", "that", "the", "their", "they", "this", "to", "was",
+        "we", "were", "with", "you",
+    ];
+
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum Format {
+        Table,
+        Json,
+        Csv,
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Options {
+        pub top: usize,
+        pub ngrams: Vec<usize>, // which of words (1), bigrams (2) and trigrams (3) to report
+        pub format: Format,
+        pub lowercase: bool,
+        pub stop_words: HashSet<String>,
+        pub files: Vec<String>, // "-" or none at all means stdin
+    }
+
+    impl Default for Options {
+        fn default() -> Options {
+            Options {
+                top: 10,
+                ngrams: vec![1, 2, 3],
+                format: Format::Table,
+                lowercase: true,
+                stop_words: HashSet::new(),
+                files: Vec::new(),
+            }
+        }
+    }
+
+    #[derive(Debug)]
+    pub enum StatsError {
+        Usage(String),
+        Io { path: String, error: io::Error },
+    }
+
+    impl fmt::Display for StatsError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                StatsError::Usage(reason) => write!(f, "{}\n{}", reason, USAGE),
+                StatsError::Io { path, error } => write!(f, "{}: {}", path, error),
+            }
+        }
+    }
+
+    impl std::error::Error for StatsError {}
+
+    pub const USAGE: &str = "usage: words [--top N] [--ngrams 1,2,3] [--format table|json|csv]
+             [--stop-words FILE] [--default-stop-words] [--keep-case] [FILE... | -]";
+
+    impl Options {
+        pub fn from_args<I: IntoIterator<Item = String>>(args: I) -> Result<Options, StatsError> {
+            let usage = |reason: String| StatsError::Usage(reason);
+            let mut options = Options::default();
+            let mut args = args.into_iter();
+            while let Some(arg) = args.next() {
+                let mut value = |flag: &str| args.next().ok_or_else(|| usage(format!("{} needs a value", flag)));
+                match arg.as_str() {
+                    "--top" => {
+                        let v = value("--top")?;
+                        options.top = v.parse().map_err(|_| usage(format!("--top expects a count, got {}", v)))?;
+                    }
+                    "--ngrams" => {
+                        let v = value("--ngrams")?;
+                        options.ngrams = v
+                            .split(',')
+                            .map(|n| match n.trim().parse() {
+                                Ok(n @ 1..=3) => Ok(n),
+                                _ => Err(usage(format!("--ngrams takes 1, 2 and/or 3, got {}", n))),
+                            })
+                            .collect::<Result<_, _>>()?;
+                    }
+                    "--format" => {
+                        options.format = match value("--format")?.as_str() {
+                            "table" => Format::Table,
+                            "json" => Format::Json,
+                            "csv" => Format::Csv,
+                            other => return Err(usage(format!("unknown format {}", other))),
+                        }
+                    }
+                    "--stop-words" => {
+                        let path = value("--stop-words")?;
+                        let io_error = |error| StatsError::Io { path: path.clone(), error };
+                        let file = BufReader::new(File::open(&path).map_err(io_error)?);
+                        for line in file.lines() {
+                            let line = line.map_err(io_error)?;
+                            let word = line.split('#').next().unwrap_or("").trim();
+                            if !word.is_empty() {
+                                options.stop_words.insert(word.to_lowercase());
+                            }
+                        }
+                    }
+                    "--default-stop-words" => options.stop_words.extend(STOP_WORDS.iter().map(|w| w.to_string())),
+                    "--keep-case" => options.lowercase = false,
+                    "-h" | "--help" => return Err(usage(String::from("word frequencies, bigrams and trigrams"))),
+                    flag if flag.starts_with("--") => return Err(usage(format!("unknown flag {}", flag))),
+                    file => options.files.push(file.to_string()),
+                }
+            }
+            Ok(options)
+        }
+    }
+
+    // trims punctuation off both ends but keeps it inside words, so
+    // "don't" and "well-known" survive; curly apostrophes become straight
+    pub fn normalise(token: &str, lowercase: bool) -> Option<String> {
+        let word = token.trim_matches(|c: char| !c.is_alphanumeric());
+        if word.is_empty() {
+            return None;
+        }
+        let word = word.replace(['\u{2019}', '\u{2018}'], "'");
+        Some(if lowercase { word.to_lowercase() } else { word })
+    }
+
+    // counts as it goes, so input is never held in memory beyond one line;
+    // n-grams run across line breaks but not across sentence ends or stop words
+    #[derive(Debug, Default)]
+    pub struct Counter {
+        counts: [HashMap<String, u64>; 3], // words, bigrams, trigrams
+        window: VecDeque<String>,
+        pub total_words: u64,
+    }
+
+    impl Counter {
+        pub fn new() -> Counter {
+            Counter::default()
+        }
+
+        pub fn feed_line(&mut self, line: &str, options: &Options) {
+            for token in line.split_whitespace() {
+                let ends_sentence = token.trim_end_matches(['"', '\'', ')', '\u{201d}', '\u{2019}']).ends_with(['.', '!', '?']);
+                match normalise(token, options.lowercase) {
+                    Some(word) if !options.stop_words.contains(&word.to_lowercase()) => self.push(word),
+                    _ => self.window.clear(),
+                }
+                if ends_sentence {
+                    self.window.clear();
+                }
+            }
+        }
+
+        fn push(&mut self, word: String) {
+            self.total_words += 1;
+            if self.window.len() == 3 {
+                self.window.pop_front();
+            }
+            self.window.push_back(word);
+            let len = self.window.len();
+            for n in 1..=len {
+                let gram: Vec<&str> = self.window.range(len - n..).map(String::as_str).collect();
+                *self.counts[n - 1].entry(gram.join(" ")).or_insert(0) += 1;
+            }
+        }
+
+        // a new file is a new text: n-grams don't carry over from the last one
+        pub fn end_of_text(&mut self) {
+            self.window.clear();
+        }
 
+        pub fn feed<R: BufRead>(&mut self, mut reader: R, options: &Options) -> io::Result<()> {
+            let mut buffer = Vec::new();
+            while reader.read_until(b'\n', &mut buffer)? > 0 {
+                self.feed_line(&String::from_utf8_lossy(&buffer), options);
+                buffer.clear();
+            }
+            self.end_of_text();
+            Ok(())
+        }
+
+        pub fn unique_words(&self) -> usize {
+            self.counts[0].len()
+        }
+
+        // most frequent first, ties alphabetically
+        pub fn top(&self, n: usize, limit: usize) -> Vec<(&str, u64)> {
+            let mut all: Vec<(&str, u64)> = self.counts[n - 1].iter().map(|(gram, &count)| (gram.as_str(), count)).collect();
+            all.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+            all.truncate(limit);
+            all
+        }
+    }
+
+    fn title(n: usize) -> &'static str {
+        ["words", "bigrams", "trigrams"][n - 1]
+    }
+
+    fn json_string(s: &str) -> String {
+        let mut out = String::from("\"");
+        for c in s.chars() {
+            match c {
+                '"' => out.push_str("\\\""),
+                '\\' => out.push_str("\\\\"),
+                c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            }
+        }
+        out.push('"');
+        out
+    }
+
+    fn csv_field(s: &str) -> String {
+        if s.contains([',', '"', '\n']) {
+            format!("\"{}\"", s.replace('"', "\"\""))
+        } else {
+            s.to_string()
+        }
+    }
+
+    pub fn report<W: Write>(counter: &Counter, options: &Options, out: &mut W) -> io::Result<()> {
+        match options.format {
+            Format::Table => {
+                writeln!(out, "{} words, {} distinct", counter.total_words, counter.unique_words())?;
+                for &n in &options.ngrams {
+                    let top = counter.top(n, options.top);
+                    let width = top.iter().map(|(gram, _)| gram.chars().count()).max().unwrap_or(0).max(title(n).len());
+                    writeln!(out, "\n{:>4}  {:<width$}  count", "rank", title(n), width = width)?;
+                    for (rank, (gram, count)) in top.iter().enumerate() {
+                        writeln!(out, "{:>4}  {:<width$}  {}", rank + 1, gram, count, width = width)?;
+                    }
+                }
+            }
+            Format::Json => {
+                write!(out, "{{\"total_words\": {}, \"unique_words\": {}", counter.total_words, counter.unique_words())?;
+                for &n in &options.ngrams {
+                    let entries: Vec<String> = counter
+                        .top(n, options.top)
+                        .iter()
+                        .map(|(gram, count)| format!("{{\"text\": {}, \"count\": {}}}", json_string(gram), count))
+                        .collect();
+                    write!(out, ", \"{}\": [{}]", title(n), entries.join(", "))?;
+                }
+                writeln!(out, "}}")?;
+            }
+            Format::Csv => {
+                writeln!(out, "kind,rank,text,count")?;
+                for &n in &options.ngrams {
+                    for (rank, (gram, count)) in counter.top(n, options.top).iter().enumerate() {
+                        writeln!(out, "{},{},{},{}", title(n), rank + 1, csv_field(gram), count)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // the whole command: read every file (or stdin), then print the report
+    pub fn run<I: IntoIterator<Item = String>>(args: I) -> Result<(), StatsError> {
+        let options = Options::from_args(args)?;
+        let mut counter = Counter::new();
+        let files = if options.files.is_empty() { vec![String::from("-")] } else { options.files.clone() };
+        for path in &files {
+            let io_error = |error| StatsError::Io { path: path.clone(), error };
+            if path == "-" {
+                counter.feed(io::stdin().lock(), &options).map_err(io_error)?;
+            } else {
+                let file = File::open(path).map_err(io_error)?;
+                counter.feed(BufReader::new(file), &options).map_err(io_error)?;
+            }
+        }
+        report(&counter, &options, &mut io::stdout().lock()).map_err(|error| StatsError::Io { path: String::from("stdout"), error })
+    }
+}
+
+mod hashing {
+    use std::collections::hash_map::RandomState;
+    use std::collections::HashMap;
+    use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+    use std::hint::black_box;
+    use std::time::{Duration, Instant};
+
+    // FNV-1a, 64 bit: one xor and one multiply per byte. Quick on short
+    // keys, but no protection against keys chosen to collide
+    pub struct FnvHasher(u64);
+
+    impl Default for FnvHasher {
+        fn default() -> FnvHasher {
+            FnvHasher(0xcbf2_9ce4_8422_2325)
+        }
+    }
+
+    impl Hasher for FnvHasher {
+        fn write(&mut self, bytes: &[u8]) {
+            for &byte in bytes {
+                self.0 ^= u64::from(byte);
+                self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+
+        fn finish(&self) -> u64 {
+            self.0
+        }
+    }
+
+    // in the style of rustc's FxHash: a word at a time, rotate, xor, multiply.
+    // Faster still on longer keys, same caveat as FNV
+    #[derive(Default)]
+    pub struct FxHasher {
+        hash: u64,
+    }
+
+    const FX_SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+    impl FxHasher {
+        fn add(&mut self, word: u64) {
+            self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(FX_SEED);
+        }
+    }
+
+    impl Hasher for FxHasher {
+        fn write(&mut self, bytes: &[u8]) {
+            let mut chunks = bytes.chunks_exact(8);
+            for chunk in &mut chunks {
+                self.add(u64::from_le_bytes(chunk.try_into().unwrap()));
+            }
+            let mut rest = chunks.remainder();
+            if rest.len() >= 4 {
+                self.add(u64::from(u32::from_le_bytes(rest[..4].try_into().unwrap())));
+                rest = &rest[4..];
+            }
+            for &byte in rest {
+                self.add(u64::from(byte));
+            }
+        }
+
+        fn write_u8(&mut self, n: u8) {
+            self.add(u64::from(n));
+        }
+
+        fn write_u32(&mut self, n: u32) {
+            self.add(u64::from(n));
+        }
+
+        fn write_u64(&mut self, n: u64) {
+            self.add(n);
+        }
+
+        fn write_usize(&mut self, n: usize) {
+            self.add(n as u64);
+        }
+
+        fn finish(&self) -> u64 {
+            self.hash
+        }
+    }
+
+    pub type FnvBuildHasher = BuildHasherDefault<FnvHasher>;
+    pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+    // the tutorial's word counter, for any hasher:
+    // `word_count::<FxBuildHasher>(text)`
+    pub fn word_count<S: BuildHasher + Default>(text: &str) -> HashMap<&str, u32, S> {
+        let mut map = HashMap::with_hasher(S::default());
+        for word in text.split_whitespace() {
+            *map.entry(word).or_insert(0) += 1;
+        }
+        map
+    }
+
+    // the tutorial's team scores, for any hasher; SipHash unless told otherwise
+    #[derive(Debug, Clone, Default)]
+    pub struct ScoreTable<S = RandomState> {
+        scores: HashMap<String, i32, S>,
+    }
+
+    impl ScoreTable {
+        pub fn new() -> ScoreTable {
+            ScoreTable::default()
+        }
+    }
+
+    impl<S: BuildHasher> ScoreTable<S> {
+        pub fn with_hasher(hasher: S) -> ScoreTable<S> {
+            ScoreTable { scores: HashMap::with_hasher(hasher) }
+        }
+
+        // overwrites, like `insert`
+        pub fn set(&mut self, team: &str, score: i32) {
+            self.scores.insert(team.to_string(), score);
+        }
+
+        // only if the team has no score yet, like `entry().or_insert()`
+        pub fn set_default(&mut self, team: &str, score: i32) -> i32 {
+            *self.scores.entry(team.to_string()).or_insert(score)
+        }
+
+        pub fn add(&mut self, team: &str, points: i32) -> i32 {
+            // skip the String allocation when the team is already there
+            if let Some(score) = self.scores.get_mut(team) {
+                *score += points;
+                return *score;
+            }
+            self.scores.insert(team.to_string(), points);
+            points
+        }
+
+        pub fn get(&self, team: &str) -> i32 {
+            self.scores.get(team).copied().unwrap_or(0)
+        }
+
+        pub fn len(&self) -> usize {
+            self.scores.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.scores.is_empty()
+        }
+
+        // highest first, ties by name
+        pub fn ranking(&self) -> Vec<(&str, i32)> {
+            let mut all: Vec<(&str, i32)> = self.scores.iter().map(|(team, &score)| (team.as_str(), score)).collect();
+            all.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+            all
+        }
+    }
+
+    // a small stand-in for criterion: warm up, size the batches so one takes
+    // about `sample_time`, then time `samples` batches
+    #[derive(Debug, Clone)]
+    pub struct Bencher {
+        pub warm_up: Duration,
+        pub sample_time: Duration,
+        pub samples: usize,
+    }
+
+    impl Default for Bencher {
+        fn default() -> Bencher {
+            Bencher {
+                warm_up: Duration::from_millis(300),
+                sample_time: Duration::from_millis(20),
+                samples: 30,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct Measurement {
+        pub name: String,
+        pub mean: Duration, // per iteration
+        pub stddev: Duration,
+        pub iterations: u64,
+    }
+
+    impl Bencher {
+        pub fn run<R, F: FnMut() -> R>(&self, name: &str, mut f: F) -> Measurement {
+            let start = Instant::now();
+            let mut warm_up_runs: u64 = 0;
+            while warm_up_runs == 0 || start.elapsed() < self.warm_up {
+                black_box(f());
+                warm_up_runs += 1;
+            }
+            let per_run = start.elapsed().as_secs_f64() / warm_up_runs as f64;
+            let batch = ((self.sample_time.as_secs_f64() / per_run) as u64).max(1);
+
+            let mut times = Vec::with_capacity(self.samples);
+            for _ in 0..self.samples.max(1) {
+                let start = Instant::now();
+                for _ in 0..batch {
+                    black_box(f());
+                }
+                times.push(start.elapsed().as_secs_f64() / batch as f64);
+            }
+            let mean = times.iter().sum::<f64>() / times.len() as f64;
+            let variance = times.iter().map(|t| (t - mean).powi(2)).sum::<f64>() / times.len() as f64;
+            Measurement {
+                name: name.to_string(),
+                mean: Duration::from_secs_f64(mean),
+                stddev: Duration::from_secs_f64(variance.sqrt()),
+                iterations: batch * times.len() as u64,
+            }
+        }
+    }
+
+    // prints one group, relative to its first entry
+    pub fn print_group(title: &str, results: &[Measurement]) {
+        println!("{}", title);
+        let base = results.first().map_or(1.0, |m| m.mean.as_secs_f64());
+        for m in results {
+            println!(
+                "  {:<10} {:>12.3?} ± {:>10.3?}  {:>5.2}x  ({} runs)",
+                m.name,
+                m.mean,
+                m.stddev,
+                base / m.mean.as_secs_f64(),
+                m.iterations
+            );
+        }
+    }
+
+    // deterministic text shaped like English: a few very common words and a
+    // long tail, so maps see both hits and inserts
+    pub fn sample_text(words: usize) -> String {
+        const COMMON: [&str; 8] = ["the", "world", "hello", "of", "and", "wonderful", "a", "to"];
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut text = String::new();
+        for _ in 0..words {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            match state % 3 {
+                0 => text.push_str(&format!("word{} ", state % 5000)),
+                _ => {
+                    text.push_str(COMMON[(state % 8) as usize]);
+                    text.push(' ');
+                }
+            }
+        }
+        text
+    }
+
+    // SipHash (the std default) against FNV and Fx on the tutorial's workloads
+    pub fn run_benchmarks(bencher: &Bencher) {
+        let text = sample_text(50_000);
+        print_group(
+            "word count, 50k words",
+            &[
+                bencher.run("siphash", || word_count::<RandomState>(&text).len()),
+                bencher.run("fnv", || word_count::<FnvBuildHasher>(&text).len()),
+                bencher.run("fx", || word_count::<FxBuildHasher>(&text).len()),
+            ],
+        );
+
+        let teams: Vec<String> = (0..1000).map(|i| format!("team {}", i)).collect();
+        fn scores<S: BuildHasher>(table: &mut ScoreTable<S>, teams: &[String]) -> i32 {
+            for (i, team) in teams.iter().cycle().take(20_000).enumerate() {
+                table.add(team, i as i32 % 7);
+            }
+            table.get(&teams[0])
+        }
+        print_group(
+            "score table, 20k updates over 1000 teams",
+            &[
+                bencher.run("siphash", || scores(&mut ScoreTable::with_hasher(RandomState::new()), &teams)),
+                bencher.run("fnv", || scores(&mut ScoreTable::with_hasher(FnvBuildHasher::default()), &teams)),
+                bencher.run("fx", || scores(&mut ScoreTable::with_hasher(FxBuildHasher::default()), &teams)),
+            ],
+        );
+
+        fn integers<S: BuildHasher + Default>() -> usize {
+            let mut map: HashMap<u64, u64, S> = HashMap::with_hasher(S::default());
+            for i in 0..10_000u64 {
+                *map.entry(i * 31 % 4096).or_insert(0) += i;
+            }
+            map.len()
+        }
+        print_group(
+            "integer keys, 10k updates",
+            &[
+                bencher.run("siphash", integers::<RandomState>),
+                bencher.run("fnv", integers::<FnvBuildHasher>),
+                bencher.run("fx", integers::<FxBuildHasher>),
+            ],
+        );
+    }
+}
+
+mod scoreboard {
+    use std::collections::{BTreeMap, HashMap};
+    use std::fmt;
+    use std::fs;
+    use std::io::{self, Write};
+    use std::path::Path;
+
+    #[derive(Debug)]
+    pub enum ScoreError {
+        BadTeam(String),
+        UnknownTeam(String),
+        Overflow(String),
+        Io(io::Error),
+        Corrupt { line: usize, reason: String },
+        Usage(String),
+    }
+
+    impl fmt::Display for ScoreError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                ScoreError::BadTeam(name) => write!(f, "{:?} can't be a team name (empty, or has a tab or line break)", name),
+                ScoreError::UnknownTeam(name) => write!(f, "no team called {}", name),
+                ScoreError::Overflow(name) => write!(f, "{}'s score is out of range", name),
+                ScoreError::Io(e) => write!(f, "I/O error: {}", e),
+                ScoreError::Corrupt { line, reason } => write!(f, "scoreboard file line {}: {}", line, reason),
+                ScoreError::Usage(reason) => write!(f, "{}\n{}", reason, USAGE),
+            }
+        }
+    }
+
+    impl std::error::Error for ScoreError {}
+
+    impl From<io::Error> for ScoreError {
+        fn from(e: io::Error) -> ScoreError {
+            ScoreError::Io(e)
+        }
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Update {
+        pub round: u32,
+        pub team: String,
+        pub delta: i64,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Standing {
+        pub rank: usize,
+        pub team: String,
+        pub score: i64,
+    }
+
+    // every score is the sum of its team's updates; the history is kept so
+    // rounds can be reported and updates undone
+    #[derive(Debug, Clone, Default)]
+    pub struct Scoreboard {
+        scores: HashMap<String, i64>,
+        history: Vec<Update>,
+        round: u32,
+    }
+
+    fn check_team(team: &str) -> Result<(), ScoreError> {
+        if team.trim().is_empty() || team.contains(['\t', '\n', '\r']) {
+            return Err(ScoreError::BadTeam(team.to_string()));
+        }
+        Ok(())
+    }
+
+    impl Scoreboard {
+        pub fn new() -> Scoreboard {
+            Scoreboard { round: 1, ..Default::default() }
+        }
+
+        pub fn round(&self) -> u32 {
+            self.round
+        }
+
+        pub fn next_round(&mut self) -> u32 {
+            self.round += 1;
+            self.round
+        }
+
+        fn apply(&mut self, update: Update) -> Result<i64, ScoreError> {
+            let current = self.scores.get(&update.team).copied().unwrap_or(0);
+            let score = current.checked_add(update.delta).ok_or_else(|| ScoreError::Overflow(update.team.clone()))?;
+            self.scores.insert(update.team.clone(), score);
+            self.round = self.round.max(update.round);
+            self.history.push(update);
+            Ok(score)
+        }
+
+        // returns the new score
+        pub fn add(&mut self, team: &str, points: i64) -> Result<i64, ScoreError> {
+            check_team(team)?;
+            self.apply(Update { round: self.round, team: team.to_string(), delta: points })
+        }
+
+        pub fn subtract(&mut self, team: &str, points: i64) -> Result<i64, ScoreError> {
+            let delta = points.checked_neg().ok_or_else(|| ScoreError::Overflow(team.to_string()))?;
+            self.add(team, delta)
+        }
+
+        pub fn score(&self, team: &str) -> Option<i64> {
+            self.scores.get(team).copied()
+        }
+
+        pub fn history(&self) -> &[Update] {
+            &self.history
+        }
+
+        // highest first; tied teams share a rank and the next rank is
+        // skipped (1, 2, 2, 4), ties listed by name
+        pub fn ranking(&self) -> Vec<Standing> {
+            let mut teams: Vec<(&String, i64)> = self.scores.iter().map(|(team, &score)| (team, score)).collect();
+            teams.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+            let mut standings: Vec<Standing> = Vec::with_capacity(teams.len());
+            for (i, (team, score)) in teams.into_iter().enumerate() {
+                let rank = match standings.last() {
+                    Some(previous) if previous.score == score => previous.rank,
+                    _ => i + 1,
+                };
+                standings.push(Standing { rank, team: team.clone(), score });
+            }
+            standings
+        }
+
+        // each round's net points per team
+        pub fn rounds(&self) -> BTreeMap<u32, BTreeMap<String, i64>> {
+            let mut rounds: BTreeMap<u32, BTreeMap<String, i64>> = BTreeMap::new();
+            for update in &self.history {
+                *rounds.entry(update.round).or_default().entry(update.team.clone()).or_insert(0) += update.delta;
+            }
+            rounds
+        }
+
+        // takes back the last `n` updates, newest first, and returns them;
+        // a team that only came from those updates disappears again
+        pub fn undo(&mut self, n: usize) -> Vec<Update> {
+            let keep = self.history.len().saturating_sub(n);
+            let undone: Vec<Update> = self.history.drain(keep..).rev().collect();
+            for update in &undone {
+                if !self.history.iter().any(|u| u.team == update.team) {
+                    self.scores.remove(&update.team);
+                } else if let Some(score) = self.scores.get_mut(&update.team) {
+                    *score -= update.delta;
+                }
+            }
+            undone
+        }
+
+        // folds in another board's updates, say from a second scorer; rounds
+        // line up by number and the merged updates can be undone like any other
+        pub fn merge(&mut self, other: &Scoreboard) -> Result<(), ScoreError> {
+            let before = self.history.len();
+            for update in &other.history {
+                if let Err(e) = self.apply(update.clone()) {
+                    self.undo(self.history.len() - before);
+                    return Err(e);
+                }
+            }
+            self.round = self.round.max(other.round);
+            Ok(())
+        }
+
+        // one update per line: round, team and points, tab separated, after
+        // a line saying which round is in play
+        pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), ScoreError> {
+            let mut out = format!("round\t{}\n", self.round);
+            for update in &self.history {
+                out.push_str(&format!("{}\t{}\t{}\n", update.round, update.team, update.delta));
+            }
+            fs::write(path, out)?;
+            Ok(())
+        }
+
+        pub fn load<P: AsRef<Path>>(path: P) -> Result<Scoreboard, ScoreError> {
+            let text = fs::read_to_string(path)?;
+            let mut board = Scoreboard::new();
+            for (i, line) in text.lines().enumerate() {
+                let corrupt = |reason: &str| ScoreError::Corrupt { line: i + 1, reason: reason.to_string() };
+                let fields: Vec<&str> = line.split('\t').collect();
+                match fields.as_slice() {
+                    [] | [""] => {}
+                    ["round", round] => board.round = round.parse().map_err(|_| corrupt("bad round"))?,
+                    [round, team, delta] => {
+                        let round = round.parse().map_err(|_| corrupt("bad round"))?;
+                        let delta = delta.parse().map_err(|_| corrupt("bad points"))?;
+                        check_team(team).map_err(|_| corrupt("bad team name"))?;
+                        board.apply(Update { round, team: team.to_string(), delta }).map_err(|_| corrupt("score out of range"))?;
+                    }
+                    _ => return Err(corrupt("expected round, team and points")),
+                }
+            }
+            Ok(board)
+        }
+
+        // a board that hasn't been saved yet starts empty
+        pub fn load_or_new<P: AsRef<Path>>(path: P) -> Result<Scoreboard, ScoreError> {
+            match Scoreboard::load(path) {
+                Err(ScoreError::Io(e)) if e.kind() == io::ErrorKind::NotFound => Ok(Scoreboard::new()),
+                result => result,
+            }
+        }
+    }
+
+    pub const USAGE: &str = "usage: scores [--file PATH] COMMAND
+  add TEAM POINTS     sub TEAM POINTS     round [next]
+  rank                show TEAM           history [ROUND]
+  undo [N]            merge FILE";
+
+    // one command against the board in `--file` (scoreboard.txt by default),
+    // saving it again if the command changed anything
+    pub fn run<I: IntoIterator<Item = String>, W: Write>(args: I, out: &mut W) -> Result<(), ScoreError> {
+        let usage = |reason: &str| ScoreError::Usage(reason.to_string());
+        let mut args: Vec<String> = args.into_iter().collect();
+        let mut path = String::from("scoreboard.txt");
+        if args.first().map(String::as_str) == Some("--file") {
+            if args.len() < 2 {
+                return Err(usage("--file needs a path"));
+            }
+            path = args.remove(1);
+            args.remove(0);
+        }
+        let mut board = Scoreboard::load_or_new(&path)?;
+        let points = |s: &str| s.parse::<i64>().map_err(|_| usage("POINTS must be a whole number"));
+
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        let changed = match args.as_slice() {
+            ["add", team, p] => {
+                writeln!(out, "{}: {}", team, board.add(team, points(p)?)?)?;
+                true
+            }
+            ["sub", team, p] => {
+                writeln!(out, "{}: {}", team, board.subtract(team, points(p)?)?)?;
+                true
+            }
+            ["round"] => {
+                writeln!(out, "round {}", board.round())?;
+                false
+            }
+            ["round", "next"] => {
+                writeln!(out, "round {} started", board.next_round())?;
+                true
+            }
+            ["rank"] => {
+                for standing in board.ranking() {
+                    writeln!(out, "{:>3}. {:<20} {:>6}", standing.rank, standing.team, standing.score)?;
+                }
+                false
+            }
+            ["show", team] => {
+                let score = board.score(team).ok_or_else(|| ScoreError::UnknownTeam(team.to_string()))?;
+                let rank = board.ranking().into_iter().find(|s| s.team == *team).map_or(0, |s| s.rank);
+                writeln!(out, "{}: {} (rank {})", team, score, rank)?;
+                false
+            }
+            ["history"] | ["history", _] => {
+                let only = match args.get(1) {
+                    Some(r) => Some(r.parse::<u32>().map_err(|_| usage("ROUND must be a number"))?),
+                    None => None,
+                };
+                for (round, teams) in board.rounds() {
+                    if only.is_none_or(|only| only == round) {
+                        let teams: Vec<String> = teams.iter().map(|(team, delta)| format!("{} {:+}", team, delta)).collect();
+                        writeln!(out, "round {}: {}", round, teams.join(", "))?;
+                    }
+                }
+                false
+            }
+            ["undo"] | ["undo", _] => {
+                let n = match args.get(1) {
+                    Some(n) => n.parse().map_err(|_| usage("N must be a count"))?,
+                    None => 1,
+                };
+                for update in board.undo(n) {
+                    writeln!(out, "undid {} {:+} in round {}", update.team, update.delta, update.round)?;
+                }
+                true
+            }
+            ["merge", other] => {
+                let other = Scoreboard::load(other)?;
+                board.merge(&other)?;
+                writeln!(out, "merged {} updates", other.history().len())?;
+                true
+            }
+            [] => return Err(usage("no command given")),
+            _ => return Err(usage(&format!("don't know how to {}", args.join(" ")))),
+        };
+        if changed {
+            board.save(&path)?;
+        }
+        Ok(())
+    }
+}
+
+mod exercises {
+    // the end-of-chapter exercises: statistics over a Vec, Pig Latin over
+    // Strings and a department directory over a HashMap
+
+    pub mod stats {
+        use std::collections::HashMap;
+
+        // the average of the middle two when the length is even
+        pub fn median(numbers: &[i32]) -> Option<f64> {
+            let mut sorted = numbers.to_vec();
+            sorted.sort_unstable();
+            let mid = sorted.len() / 2;
+            match sorted.len() {
+                0 => None,
+                n if n % 2 == 1 => Some(f64::from(sorted[mid])),
+                _ => Some((f64::from(sorted[mid - 1]) + f64::from(sorted[mid])) / 2.0),
+            }
+        }
+
+        // every value that appears most often, smallest first
+        pub fn modes(numbers: &[i32]) -> Vec<i32> {
+            let mut counts = HashMap::new();
+            for &n in numbers {
+                *counts.entry(n).or_insert(0) += 1;
+            }
+            let most = counts.values().copied().max().unwrap_or(0);
+            let mut modes: Vec<i32> = counts.into_iter().filter(|&(_, count)| count == most).map(|(n, _)| n).collect();
+            modes.sort_unstable();
+            modes
+        }
+
+        // the smallest mode when there's a tie
+        pub fn mode(numbers: &[i32]) -> Option<i32> {
+            modes(numbers).first().copied()
+        }
+    }
+
+    pub mod pig_latin {
+        // Latin letters only: ASCII plus Latin-1 and Latin Extended-A/B,
+        // without the × and ÷ that sit among them
+        fn is_latin(c: char) -> bool {
+            c.is_ascii_alphabetic() || (matches!(c, '\u{c0}'..='\u{24f}') && c != '\u{d7}' && c != '\u{f7}')
+        }
+
+        fn is_combining(c: char) -> bool {
+            matches!(c, '\u{300}'..='\u{36f}')
+        }
+
+        fn is_vowel(c: char) -> bool {
+            let lower = c.to_lowercase().next().unwrap_or(c);
+            "aeiouàáâãäåāăąæèéêëēĕėęěìíîïĩīĭįıòóôõöøōŏőœùúûüũūŭůűų".contains(lower)
+        }
+
+        // "first" -> "irst-fay", "apple" -> "apple-hay"; keeps a leading
+        // capital where it was ("Hello" -> "Ello-hay") and moves a letter
+        // together with its combining accents
+        pub fn word(word: &str) -> String {
+            let mut chars = word.chars();
+            let first = match chars.next() {
+                Some(c) if is_latin(c) => c,
+                _ => return word.to_string(), // other scripts are left alone
+            };
+            if is_vowel(first) {
+                return format!("{}-hay", word);
+            }
+            let rest = chars.as_str();
+            let marks_end = rest.find(|c: char| !is_combining(c)).unwrap_or(rest.len());
+            let (marks, rest) = rest.split_at(marks_end);
+            if rest.is_empty() {
+                return format!("{}{}-ay", first, marks);
+            }
+            let moved: String = if first.is_uppercase() { first.to_lowercase().collect() } else { first.to_string() };
+            let mut rest_chars = rest.chars();
+            let rest = match rest_chars.next() {
+                Some(c) if first.is_uppercase() => c.to_uppercase().chain(rest_chars).collect(),
+                _ => rest.to_string(),
+            };
+            format!("{}-{}{}ay", rest, moved, marks)
+        }
+
+        // converts every word and leaves spacing and punctuation where it was;
+        // apostrophes inside a word ("don't") stay part of it
+        pub fn translate(text: &str) -> String {
+            let mut out = String::with_capacity(text.len() * 2);
+            let mut current = String::new();
+            let mut chars = text.chars().peekable();
+            while let Some(c) = chars.next() {
+                let inner_apostrophe = (c == '\'' || c == '\u{2019}')
+                    && !current.is_empty()
+                    && chars.peek().is_some_and(|next| next.is_alphabetic());
+                if c.is_alphabetic() || is_combining(c) || inner_apostrophe {
+                    current.push(c);
+                } else {
+                    if !current.is_empty() {
+                        out.push_str(&word(&current));
+                        current.clear();
+                    }
+                    out.push(c);
+                }
+            }
+            if !current.is_empty() {
+                out.push_str(&word(&current));
+            }
+            out
+        }
+    }
+
+    pub mod directory {
+        use std::collections::HashMap;
+        use std::fmt;
+        use std::io::{self, BufRead, Write};
+
+        #[derive(Debug, PartialEq)]
+        pub enum DirectoryError {
+            Unrecognised(String),
+            Incomplete(&'static str), // what the command is missing
+            AlreadyThere { name: String, department: String },
+            NotThere { name: String, department: String },
+            UnknownDepartment(String),
+        }
+
+        impl fmt::Display for DirectoryError {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                match self {
+                    DirectoryError::Unrecognised(line) => write!(f, "didn't understand {:?}; try \"Add Sally to Engineering\"", line),
+                    DirectoryError::Incomplete(usage) => write!(f, "expected {}", usage),
+                    DirectoryError::AlreadyThere { name, department } => write!(f, "{} is already in {}", name, department),
+                    DirectoryError::NotThere { name, department } => write!(f, "{} isn't in {}", name, department),
+                    DirectoryError::UnknownDepartment(department) => write!(f, "there is no {} department", department),
+                }
+            }
+        }
+
+        impl std::error::Error for DirectoryError {}
+
+        #[derive(Debug, Clone, PartialEq)]
+        pub enum Command {
+            Add { name: String, department: String },
+            Remove { name: String, department: String },
+            List(Option<String>), // one department, or everyone
+            Quit,
+        }
+
+        // splits "Sally to Engineering" at the first standalone "to" (any
+        // case) with something on both sides
+        fn split_at_word<'a>(text: &'a str, word: &str) -> Option<(&'a str, &'a str)> {
+            for part in text.split(char::is_whitespace) {
+                let start = part.as_ptr() as usize - text.as_ptr() as usize;
+                if part.eq_ignore_ascii_case(word) {
+                    let (left, right) = (text[..start].trim(), text[start + part.len()..].trim());
+                    if !left.is_empty() && !right.is_empty() {
+                        return Some((left, right));
+                    }
+                }
+            }
+            None
+        }
+
+        impl Command {
+            // "Add Sally to Engineering", "Remove Sally from Engineering",
+            // "List Engineering", "List" and "Quit"; keywords in any case
+            pub fn parse(line: &str) -> Result<Command, DirectoryError> {
+                let line = line.trim();
+                let (verb, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+                let rest = rest.trim();
+                match verb.to_lowercase().as_str() {
+                    "add" => split_at_word(rest, "to")
+                        .map(|(name, department)| Command::Add { name: name.to_string(), department: department.to_string() })
+                        .ok_or(DirectoryError::Incomplete("Add NAME to DEPARTMENT")),
+                    "remove" => split_at_word(rest, "from")
+                        .map(|(name, department)| Command::Remove { name: name.to_string(), department: department.to_string() })
+                        .ok_or(DirectoryError::Incomplete("Remove NAME from DEPARTMENT")),
+                    "list" if rest.is_empty() || rest.eq_ignore_ascii_case("all") => Ok(Command::List(None)),
+                    "list" => Ok(Command::List(Some(rest.to_string()))),
+                    "quit" | "exit" if rest.is_empty() => Ok(Command::Quit),
+                    _ => Err(DirectoryError::Unrecognised(line.to_string())),
+                }
+            }
+        }
+
+        // department -> people, each list kept sorted
+        #[derive(Debug, Clone, Default)]
+        pub struct Directory {
+            departments: HashMap<String, Vec<String>>,
+        }
+
+        impl Directory {
+            pub fn new() -> Directory {
+                Directory::default()
+            }
+
+            pub fn add(&mut self, name: &str, department: &str) -> Result<(), DirectoryError> {
+                let people = self.departments.entry(department.to_string()).or_default();
+                match people.binary_search_by(|p| p.as_str().cmp(name)) {
+                    Ok(_) => Err(DirectoryError::AlreadyThere { name: name.to_string(), department: department.to_string() }),
+                    Err(at) => {
+                        people.insert(at, name.to_string());
+                        Ok(())
+                    }
+                }
+            }
+
+            // an emptied department goes away
+            pub fn remove(&mut self, name: &str, department: &str) -> Result<(), DirectoryError> {
+                let people = self
+                    .departments
+                    .get_mut(department)
+                    .ok_or_else(|| DirectoryError::UnknownDepartment(department.to_string()))?;
+                let at = people
+                    .binary_search_by(|p| p.as_str().cmp(name))
+                    .map_err(|_| DirectoryError::NotThere { name: name.to_string(), department: department.to_string() })?;
+                people.remove(at);
+                if people.is_empty() {
+                    self.departments.remove(department);
+                }
+                Ok(())
+            }
+
+            pub fn department(&self, department: &str) -> Option<&[String]> {
+                self.departments.get(department).map(Vec::as_slice)
+            }
+
+            // departments in alphabetical order, people sorted within each
+            pub fn all(&self) -> Vec<(&str, &[String])> {
+                let mut all: Vec<(&str, &[String])> =
+                    self.departments.iter().map(|(department, people)| (department.as_str(), people.as_slice())).collect();
+                all.sort_by(|a, b| a.0.cmp(b.0));
+                all
+            }
+
+            // what the prompt prints back
+            pub fn execute(&mut self, command: Command) -> Result<String, DirectoryError> {
+                match command {
+                    Command::Add { name, department } => {
+                        self.add(&name, &department)?;
+                        Ok(format!("added {} to {}", name, department))
+                    }
+                    Command::Remove { name, department } => {
+                        self.remove(&name, &department)?;
+                        Ok(format!("removed {} from {}", name, department))
+                    }
+                    Command::List(Some(department)) => {
+                        let people = self
+                            .department(&department)
+                            .ok_or_else(|| DirectoryError::UnknownDepartment(department.clone()))?;
+                        Ok(format!("{}: {}", department, people.join(", ")))
+                    }
+                    Command::List(None) if self.departments.is_empty() => Ok(String::from("nobody yet")),
+                    Command::List(None) => Ok(self
+                        .all()
+                        .iter()
+                        .map(|(department, people)| format!("{}: {}", department, people.join(", ")))
+                        .collect::<Vec<_>>()
+                        .join("\n")),
+                    Command::Quit => Ok(String::new()),
+                }
+            }
+
+            // a prompt loop until "Quit" or end of input; mistakes are
+            // reported and the loop carries on
+            pub fn run_interactive<R: BufRead, W: Write>(&mut self, input: R, output: &mut W) -> io::Result<()> {
+                write!(output, "> ")?;
+                output.flush()?;
+                for line in input.lines() {
+                    let line = line?;
+                    if !line.trim().is_empty() {
+                        match Command::parse(&line) {
+                            Ok(Command::Quit) => return Ok(()),
+                            Ok(command) => match self.execute(command) {
+                                Ok(reply) => writeln!(output, "{}", reply)?,
+                                Err(e) => writeln!(output, "{}", e)?,
+                            },
+                            Err(e) => writeln!(output, "{}", e)?,
+                        }
+                    }
+                    write!(output, "> ")?;
+                    output.flush()?;
+                }
+                writeln!(output)
+            }
+        }
+    }
+}